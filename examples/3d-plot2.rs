@@ -6,7 +6,7 @@ use parking_lot::RwLock;
 use plotters::coord::Shift;
 use plotters::drawing::DrawingArea;
 use plotters::prelude::*;
-use plotters_gpui::backend::GpuiBackend;
+use plotters_backend::DrawingErrorKind;
 use plotters_gpui::element::{PlottersChart, PlottersDrawAreaModel, PlottersDrawAreaViewer};
 use std::rc::Rc;
 
@@ -20,14 +20,16 @@ fn pdf(x: f64, y: f64) -> f64 {
 }
 
 struct MainViewer {
+    model: Rc<RwLock<PlottersDrawAreaModel>>,
     figure: Entity<PlottersDrawAreaViewer>,
 }
 
 impl MainViewer {
     fn new(model: Rc<RwLock<PlottersDrawAreaModel>>, cx: &mut App) -> Self {
-        let figure = PlottersDrawAreaViewer::with_shared_model(model);
+        let figure = PlottersDrawAreaViewer::with_shared_model(model.clone());
 
         Self {
+            model,
             figure: cx.new(move |_| figure),
         }
     }
@@ -35,7 +37,8 @@ impl MainViewer {
 
 impl Render for MainViewer {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        cx.defer_in(window, move |_, _, cx| {
+        cx.defer_in(window, move |this, _, cx| {
+            this.model.write().mark_dirty();
             cx.notify();
         });
 
@@ -74,11 +77,11 @@ impl MyChart {
     }
 }
 
-impl PlottersChart for MyChart {
+impl<DB: DrawingBackend> PlottersChart<DB> for MyChart {
     fn plot(
         &mut self,
-        root: &DrawingArea<GpuiBackend, Shift>,
-    ) -> Result<(), plotters_gpui::DrawingErrorKind> {
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
         root.fill(&WHITE).unwrap();
 
         self.next();
@@ -8,19 +8,21 @@ use parking_lot::RwLock;
 use plotters::coord::Shift;
 use plotters::drawing::DrawingArea;
 use plotters::prelude::*;
-use plotters_gpui::backend::GpuiBackend;
+use plotters_backend::DrawingErrorKind;
 use plotters_gpui::element::{PlottersChart, PlottersDrawAreaModel, PlottersDrawAreaViewer};
 
 struct MainViewer {
+    model: Rc<RwLock<PlottersDrawAreaModel>>,
     figure: Entity<PlottersDrawAreaViewer>,
     animation: bool,
 }
 
 impl MainViewer {
     fn new(model: Rc<RwLock<PlottersDrawAreaModel>>, cx: &mut App) -> Self {
-        let figure = PlottersDrawAreaViewer::with_shared_model(model);
+        let figure = PlottersDrawAreaViewer::with_shared_model(model.clone());
 
         Self {
+            model,
             figure: cx.new(move |_| figure),
             animation: false,
         }
@@ -31,6 +33,7 @@ impl Render for MainViewer {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         cx.defer_in(window, move |this, _, cx| {
             if this.animation {
+                this.model.write().mark_dirty();
                 cx.notify();
             }
         });
@@ -71,11 +74,11 @@ impl Animation {
     }
 }
 
-impl PlottersChart for Animation {
+impl<DB: DrawingBackend> PlottersChart<DB> for Animation {
     fn plot(
         &mut self,
-        root: &DrawingArea<GpuiBackend, Shift>,
-    ) -> Result<(), plotters_gpui::DrawingErrorKind> {
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
         let mut chart = ChartBuilder::on(root)
             .caption("Animation", ("sans-serif", 24).into_font())
             .margin(5)
@@ -6,7 +6,7 @@ use parking_lot::RwLock;
 use plotters::coord::Shift;
 use plotters::drawing::DrawingArea;
 use plotters::prelude::*;
-use plotters_gpui::backend::GpuiBackend;
+use plotters_backend::DrawingErrorKind;
 use plotters_gpui::element::{PlottersChart, PlottersDrawAreaModel, PlottersDrawAreaViewer};
 use std::collections::VecDeque;
 use std::rc::Rc;
@@ -14,15 +14,17 @@ use std::time::SystemTime;
 use sysinfo::{Pid, ProcessesToUpdate, System};
 
 struct MainViewer {
+    model: Rc<RwLock<PlottersDrawAreaModel>>,
     figure: Entity<PlottersDrawAreaViewer>,
     animation: bool,
 }
 
 impl MainViewer {
     fn new(model: Rc<RwLock<PlottersDrawAreaModel>>, cx: &mut App) -> Self {
-        let figure = PlottersDrawAreaViewer::with_shared_model(model);
+        let figure = PlottersDrawAreaViewer::with_shared_model(model.clone());
 
         Self {
+            model,
             figure: cx.new(move |_| figure),
             animation: false,
         }
@@ -33,6 +35,7 @@ impl Render for MainViewer {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         cx.defer_in(window, move |this, _, cx| {
             if this.animation {
+                this.model.write().mark_dirty();
                 cx.notify();
             }
         });
@@ -90,11 +93,11 @@ impl CpuUsage {
     }
 }
 
-impl PlottersChart for CpuUsage {
+impl<DB: DrawingBackend> PlottersChart<DB> for CpuUsage {
     fn plot(
         &mut self,
-        root: &DrawingArea<GpuiBackend, Shift>,
-    ) -> Result<(), plotters_gpui::DrawingErrorKind> {
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
         self.try_sample();
         let line = self.get_line();
         let x_min = line.first().map(|(x, _)| *x).unwrap_or(0.0);
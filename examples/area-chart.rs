@@ -5,8 +5,13 @@ use gpui::{
 use parking_lot::RwLock;
 use plotters::coord::Shift;
 use plotters::prelude::*;
-use plotters_gpui::backend::GpuiBackend;
+use plotters_backend::DrawingErrorKind;
+use plotters_gpui::backend::BackendState;
+use plotters_gpui::clip::with_clip;
+use plotters_gpui::coords::ChartCoords;
 use plotters_gpui::element::*;
+use plotters_gpui::gradient::{with_gradient, LinearGradient};
+use plotters_gpui::view::ViewRange;
 use rand::SeedableRng as _;
 use rand_distr::{Distribution as _, Normal};
 use rand_xorshift::XorShiftRng;
@@ -39,6 +44,8 @@ impl Render for MainViewer {
 
 struct AreaChart {
     data: Vec<f64>,
+    view: ViewRange,
+    coords: Option<ChartCoords>,
 }
 
 impl AreaChart {
@@ -55,21 +62,31 @@ impl AreaChart {
                 .collect()
         };
 
-        Self { data }
+        Self {
+            data,
+            view: ViewRange::default(),
+            coords: None,
+        }
     }
 }
 
-impl PlottersChart for AreaChart {
+impl<DB: BackendState> PlottersChart<DB> for AreaChart {
     fn plot(
         &mut self,
-        root: &DrawingArea<GpuiBackend, Shift>,
-    ) -> Result<(), plotters_gpui::DrawingErrorKind> {
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
+        // scroll to zoom, drag to pan and double click to reset
         let mut chart = ChartBuilder::on(root)
             .set_label_area_size(LabelAreaPosition::Left, 60)
             .set_label_area_size(LabelAreaPosition::Bottom, 60)
             .caption("Area Chart Demo", ("sans-serif", 40))
-            .build_cartesian_2d(0..(self.data.len() - 1), 0.0..1500.0)
+            .build_cartesian_2d(
+                self.view.x_range(0.0..(self.data.len() - 1) as f64),
+                self.view.y_range(0.0..1500.0),
+            )
             .unwrap();
+        // lets the viewer map the mouse into the data, zoom and pan follow the plotting area
+        self.coords = Some(ChartCoords::new(&chart));
 
         chart
             .configure_mesh()
@@ -78,23 +95,42 @@ impl PlottersChart for AreaChart {
             .draw()
             .unwrap();
 
-        chart
-            .draw_series(
-                AreaSeries::new(
-                    (0..).zip(self.data.iter()).map(|(x, y)| (x, *y)),
-                    0.0,
-                    RED.mix(0.2),
+        let fade = LinearGradient::vertical()
+            .stop(0.0, RED.mix(0.4).to_backend_color())
+            .stop(1.0, RED.mix(0.0).to_backend_color());
+        let plotting_area = chart.plotting_area().strip_coord_spec();
+        with_clip(&plotting_area, || {
+            with_gradient(root, fade, || {
+                chart.draw_series(
+                    AreaSeries::new(
+                        (0..).zip(self.data.iter()).map(|(x, y)| (x as f64, *y)),
+                        0.0,
+                        RED.mix(0.2),
+                    )
+                    .border_style(RED),
                 )
-                .border_style(RED),
-            )
-            .unwrap();
+            })
+        })
+        .unwrap()
+        .unwrap()
+        .unwrap();
 
         Ok(())
     }
+
+    fn set_view(&mut self, view: ViewRange) {
+        self.view = view;
+    }
+
+    fn coords(&self) -> Option<ChartCoords> {
+        self.coords.clone()
+    }
 }
 
 fn main_viewer(cx: &mut App) -> MainViewer {
-    let figure = PlottersDrawAreaModel::new(Box::new(AreaChart::new()));
+    let mut figure = PlottersDrawAreaModel::new(Box::new(AreaChart::new()));
+    figure.interactive = true;
+    figure.crosshair = true;
     MainViewer::new(Rc::new(RwLock::new(figure)), cx)
 }
 
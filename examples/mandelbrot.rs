@@ -8,7 +8,7 @@ use parking_lot::RwLock;
 use plotters::coord::Shift;
 use plotters::drawing::DrawingArea;
 use plotters::prelude::*;
-use plotters_gpui::backend::GpuiBackend;
+use plotters_backend::DrawingErrorKind;
 use plotters_gpui::element::{PlottersChart, PlottersDrawAreaModel, PlottersDrawAreaViewer};
 
 use std::ops::Range;
@@ -65,11 +65,11 @@ impl Render for MainViewer {
 }
 
 struct MyChart;
-impl PlottersChart for MyChart {
+impl<DB: DrawingBackend> PlottersChart<DB> for MyChart {
     fn plot(
         &mut self,
-        root: &DrawingArea<GpuiBackend, Shift>,
-    ) -> Result<(), plotters_gpui::DrawingErrorKind> {
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
         let mut chart = ChartBuilder::on(root)
             .margin(20)
             .x_label_area_size(10)
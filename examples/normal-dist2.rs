@@ -6,7 +6,7 @@ use parking_lot::RwLock;
 use plotters::coord::Shift;
 use plotters::drawing::DrawingArea;
 use plotters::prelude::*;
-use plotters_gpui::backend::GpuiBackend;
+use plotters_backend::DrawingErrorKind;
 use plotters_gpui::element::{PlottersChart, PlottersDrawAreaModel, PlottersDrawAreaViewer};
 use rand::SeedableRng as _;
 use rand_distr::num_traits::Float as _;
@@ -40,11 +40,11 @@ impl Render for MainViewer {
 }
 
 struct MyChart;
-impl PlottersChart for MyChart {
+impl<DB: DrawingBackend> PlottersChart<DB> for MyChart {
     fn plot(
         &mut self,
-        root: &DrawingArea<GpuiBackend, Shift>,
-    ) -> Result<(), plotters_gpui::DrawingErrorKind> {
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
         let sd = 0.60;
 
         let random_points: Vec<f64> = {
@@ -6,8 +6,9 @@ use gpui::{
 use parking_lot::RwLock;
 use plotters::coord::Shift;
 use plotters::prelude::*;
-use plotters_gpui::backend::GpuiBackend;
+use plotters_backend::DrawingErrorKind;
 use plotters_gpui::element::*;
+use plotters_gpui::tooltip::DataPoints;
 use std::rc::Rc;
 
 fn parse_time(t: &str) -> chrono::NaiveDate {
@@ -43,6 +44,7 @@ impl Render for MainViewer {
 
 struct StockChart {
     data: Vec<(&'static str, f32, f32, f32, f32)>,
+    points: Option<Rc<DataPoints>>,
 }
 
 impl StockChart {
@@ -80,15 +82,15 @@ impl StockChart {
             ("2019-03-14", 114.54, 115.2, 114.33, 114.59),
         ];
 
-        Self { data }
+        Self { data, points: None }
     }
 }
 
-impl PlottersChart for StockChart {
+impl<DB: DrawingBackend> PlottersChart<DB> for StockChart {
     fn plot(
         &mut self,
-        root: &DrawingArea<GpuiBackend, Shift>,
-    ) -> Result<(), plotters_gpui::DrawingErrorKind> {
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
         let (to_date, from_date) = (
             parse_time(self.data[0].0) + Duration::days(1),
             parse_time(self.data[29].0) - Duration::days(1),
@@ -113,8 +115,21 @@ impl PlottersChart for StockChart {
             }))
             .unwrap();
 
+        // hovering a candle shows its closing price
+        let mut points = DataPoints::default();
+        points.add_series(
+            &chart,
+            "Close",
+            self.data.iter().map(|x| (parse_time(x.0), x.4)),
+        );
+        self.points = Some(Rc::new(points));
+
         Ok(())
     }
+
+    fn data_points(&self) -> Option<Rc<DataPoints>> {
+        self.points.clone()
+    }
 }
 
 fn main_viewer(cx: &mut App) -> MainViewer {
@@ -1,31 +1,676 @@
-use crate::line::Line;
-use crate::utils::{color_to_hsla, coord_to_point};
-use gpui::{bounds, fill, point, px, App, Bounds, Pixels, SharedString, Size, TextRun, Window};
+use crate::bitmap::{rgb_pixels, BitmapCache};
+use crate::error::Error;
+use crate::fill::FillRule;
+use crate::font::FontAliases;
+use crate::gradient::{GradientAxis, LinearGradient};
+use crate::line::{Line, StrokeStyle};
+use crate::raster::{PixelRaster, RasterPatch};
+use crate::record::{
+    Color, DisplayList, DrawCommand, Stroke, TextMetrics, TextStyle as RecordedTextStyle,
+};
+use crate::utils::{color_to_hsla, coord_to_point, snap_to_pixel_center, text_lines};
+use gpui::{
+    bounds, fill, linear_color_stop, linear_gradient, point, px, size, App, Bounds, ContentMask,
+    Corners, Font, Path, PathBuilder, PathStyle, Pixels, Point, ShapedLine, SharedString, Size,
+    TextRun, Window,
+};
 use plotters_backend::{
     text_anchor::{HPos, VPos},
     BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingBackend, DrawingErrorKind,
+    FontTransform,
 };
+use tracing::warn;
 
 /// The embedded backend for plotters in gpui
 pub struct GpuiBackend<'a> {
     bounds: Bounds<Pixels>,
     window: &'a mut Window,
     cx: &'a mut App,
+    pixels: PixelRaster,
+    immediate_pixels: bool,
+    /// consecutive `draw_line` calls with the same width and color, painted as one path
+    line_batch: Vec<Line>,
+    clips: Vec<Bounds<Pixels>>,
+    stroke_styles: Vec<StrokeStyle>,
+    fill_rules: Vec<FillRule>,
+    gradients: Vec<LinearGradient>,
+    /// scale factor of the window, device pixels per logical pixel
+    scale_factor: f32,
+    /// backend pixels per logical pixel, either 1 or `scale_factor`
+    scale: f32,
+    recording: Option<&'a mut DisplayList>,
+    paint: bool,
 }
 
 impl<'a> GpuiBackend<'a> {
     /// Create a new embedded backend
     pub fn new(bounds: Bounds<Pixels>, window: &'a mut Window, cx: &'a mut App) -> Self {
-        Self { bounds, window, cx }
+        let pixels = PixelRaster::new(bounds.size.width.0 as u32, bounds.size.height.0 as u32);
+        let scale_factor = window.scale_factor();
+        Self {
+            bounds,
+            window,
+            cx,
+            pixels,
+            immediate_pixels: false,
+            line_batch: vec![],
+            clips: vec![],
+            stroke_styles: vec![],
+            fill_rules: vec![],
+            gradients: vec![],
+            scale_factor,
+            scale: 1.0,
+            recording: None,
+            paint: true,
+        }
+    }
+
+    /// Expose device pixels instead of logical pixels to plotters, so charts are rendered at
+    /// the full resolution of HiDPI displays. All sizes given to plotters, including font sizes
+    /// and stroke widths, are then in device pixels.
+    pub fn device_pixels(mut self, enabled: bool) -> Self {
+        self.scale = if enabled { self.scale_factor } else { 1.0 };
+        let (width, height) = self.get_size();
+        self.pixels = PixelRaster::new(width, height);
+        if let Some(list) = self.recording.as_deref_mut() {
+            list.size = (width, height);
+        }
+        self
+    }
+
+    /// Skip all painting and only lay out, e.g. to [record](Self::record_into) a chart at
+    /// another size than it is shown at. gpui only allows painting during the paint phase.
+    pub fn paint(mut self, enabled: bool) -> Self {
+        self.paint = enabled;
+        self
+    }
+
+    /// Also record everything drawn into `list`, replacing what it held before, so it can be
+    /// [replayed](DisplayList::replay) without running the chart again
+    pub fn record_into(mut self, list: &'a mut DisplayList) -> Self {
+        *list = DisplayList::new(self.get_size());
+        self.recording = Some(list);
+        self
+    }
+
+    /// Paint every `draw_pixel` call as its own quad instead of batching them into one image
+    pub fn immediate_pixels(mut self, immediate: bool) -> Self {
+        self.immediate_pixels = immediate;
+        self
+    }
+
+    fn record(&mut self, command: impl FnOnce() -> DrawCommand) {
+        if let Some(list) = self.recording.as_deref_mut() {
+            list.commands.push(command());
+        }
+    }
+
+    fn point(&self, coord: BackendCoord) -> Point<Pixels> {
+        coord_to_point(self.bounds.origin, coord, self.scale)
+    }
+
+    /// Like [`Self::point`], but snapped to a device pixel centre when a stroke of `width`
+    /// backend pixels covers an odd number of device pixels
+    fn stroke_point(&self, coord: BackendCoord, width: u32) -> Point<Pixels> {
+        let point = self.point(coord);
+        let device_width = width as f32 / self.scale * self.scale_factor;
+        if device_width.fract() == 0.0 && device_width as u32 % 2 == 1 {
+            snap_to_pixel_center(point, self.scale_factor)
+        } else {
+            point
+        }
+    }
+
+    /// Convert a length in backend pixels to logical pixels
+    fn length(&self, value: impl Into<f64>) -> Pixels {
+        px(value.into() as f32 / self.scale)
+    }
+
+    /// Fill `contours` with the current fill rule without recording them
+    fn paint_contours(
+        &mut self,
+        contours: Vec<Vec<BackendCoord>>,
+        color: BackendColor,
+    ) -> Result<(), DrawingErrorKind<Error>> {
+        self.flush_pending()?;
+        if contours.iter().all(|contour| contour.is_empty()) {
+            return Ok(());
+        }
+
+        let options = self
+            .fill_rules
+            .last()
+            .copied()
+            .unwrap_or_default()
+            .fill_options();
+        let mut builder = PathBuilder::fill().with_style(PathStyle::Fill(options));
+        let mut points = vec![];
+        for contour in contours {
+            let mut iter = contour.into_iter().map(|coord| self.point(coord));
+            let Some(start) = iter.next() else {
+                continue;
+            };
+            builder.move_to(start);
+            points.push(start);
+            for point in iter {
+                builder.line_to(point);
+                points.push(point);
+            }
+            builder.close();
+        }
+
+        let path = builder
+            .build()
+            .map_err(|err| Error::PathBuild(err.to_string()))?;
+        self.paint_fill(path, &points, color);
+        Ok(())
+    }
+
+    /// Paint a fill path through `points` with the current gradient, or with `color` when there
+    /// is none
+    fn paint_fill(&mut self, path: Path<Pixels>, points: &[Point<Pixels>], color: BackendColor) {
+        let Some(gradient) = self
+            .gradients
+            .last()
+            .filter(|g| !g.stops.is_empty())
+            .cloned()
+        else {
+            let color = color_to_hsla(color);
+            self.clipped(|window, _| window.paint_path(path, color));
+            return;
+        };
+
+        let min = |f: fn(&Point<Pixels>) -> f32| points.iter().map(f).fold(f32::MAX, f32::min);
+        let max = |f: fn(&Point<Pixels>) -> f32| points.iter().map(f).fold(f32::MIN, f32::max);
+        let (left, top) = (min(|p| p.x.0), min(|p| p.y.0));
+        let (right, bottom) = (max(|p| p.x.0), max(|p| p.y.0));
+        // gpui maps gradients onto the bounds of the path, with the angle as in CSS
+        let (angle, start, extent) = match gradient.axis {
+            GradientAxis::Horizontal => (90.0, left, right - left),
+            GradientAxis::Vertical => (180.0, top, bottom - top),
+        };
+
+        // gpui gradients have two stops, so every pair of stops paints the path again, masked
+        // to its band. The first and last band extend past the path to cover its edges.
+        let stops = &gradient.stops;
+        let bands = stops.len().saturating_sub(1).max(1);
+        self.clipped(|window, _| {
+            for index in 0..bands {
+                let (a, b) = (&stops[index], &stops[(index + 1).min(stops.len() - 1)]);
+                let from = if index == 0 {
+                    f32::MIN
+                } else {
+                    start + a.offset * extent
+                };
+                let to = if index + 1 == bands {
+                    f32::MAX
+                } else {
+                    start + b.offset * extent
+                };
+                if from >= to {
+                    continue;
+                }
+                let background = if b.offset > a.offset {
+                    linear_gradient(
+                        angle,
+                        linear_color_stop(color_to_hsla(a.color.into()), a.offset),
+                        linear_color_stop(color_to_hsla(b.color.into()), b.offset),
+                    )
+                } else {
+                    let color = if index + 1 == bands { b.color } else { a.color };
+                    color_to_hsla(color.into()).into()
+                };
+                let band = match gradient.axis {
+                    GradientAxis::Horizontal => Bounds::from_corners(
+                        point(px(from.max(left - 1.0)), px(top - 1.0)),
+                        point(px(to.min(right + 1.0)), px(bottom + 1.0)),
+                    ),
+                    GradientAxis::Vertical => Bounds::from_corners(
+                        point(px(left - 1.0), px(from.max(top - 1.0))),
+                        point(px(right + 1.0), px(to.min(bottom + 1.0))),
+                    ),
+                };
+                let path = path.clone();
+                window.with_content_mask(Some(ContentMask { bounds: band }), |window| {
+                    window.paint_path(path, background)
+                });
+            }
+        });
+    }
+
+    /// A line with the width, color and current stroke style of a plotters style
+    fn line<S: BackendStyle>(&self, points: Vec<Point<Pixels>>, style: &S) -> Line {
+        let mut line = Line::new()
+            .width(self.length(style.stroke_width()))
+            .color(color_to_hsla(style.color()));
+        line.points = points;
+        if let Some(stroke) = self.stroke_styles.last() {
+            line = line.cap(stroke.cap).join(stroke.join).dash(
+                stroke
+                    .dash
+                    .iter()
+                    .map(|d| self.length(*d))
+                    .collect::<Vec<_>>(),
+                self.length(stroke.dash_offset),
+            );
+        }
+        line
+    }
+
+    fn clip_bounds(&self) -> Bounds<Pixels> {
+        self.clips.last().copied().unwrap_or(self.bounds)
+    }
+
+    /// Run `paint` with the content mask of the current clip installed, unless painting is
+    /// disabled
+    fn clipped<R>(&mut self, paint: impl FnOnce(&mut Window, &mut App) -> R) -> Option<R> {
+        if !self.paint {
+            return None;
+        }
+        let mask = ContentMask {
+            bounds: self.clip_bounds(),
+        };
+        let cx = &mut *self.cx;
+        Some(
+            self.window
+                .with_content_mask(Some(mask), |window| paint(window, cx)),
+        )
+    }
+
+    /// Paint whatever is batched, so it stays below the primitive drawn next.
+    /// At most one of the batches is non-empty at any time, which keeps the paint order.
+    fn flush_pending(&mut self) -> Result<(), DrawingErrorKind<Error>> {
+        self.flush_lines()?;
+        self.flush_pixels()
+    }
+
+    fn flush_lines(&mut self) -> Result<(), DrawingErrorKind<Error>> {
+        let Some(first) = self.line_batch.first() else {
+            return Ok(());
+        };
+        let color = first.color;
+        let mut builder = PathBuilder::stroke(first.width);
+        builder.style = PathStyle::Stroke(first.stroke_options());
+        for line in self.line_batch.drain(..) {
+            line.append_to(&mut builder);
+        }
+        let path = builder
+            .build()
+            .map_err(|err| Error::PathBuild(err.to_string()))?;
+
+        self.clipped(|window, _| window.paint_path(path, color));
+        Ok(())
+    }
+
+    /// Paint the pixels batched so far
+    fn flush_pixels(&mut self) -> Result<(), DrawingErrorKind<Error>> {
+        match self.pixels.take() {
+            Some(patch) => self.paint_patch(patch),
+            None => Ok(()),
+        }
+    }
+
+    /// Paint pixels taken out of a raster at their place on the canvas
+    fn paint_patch(&mut self, patch: RasterPatch) -> Result<(), DrawingErrorKind<Error>> {
+        if !self.paint {
+            return Ok(());
+        }
+        let (width, height) = patch.size;
+        let image = BitmapCache::bgra_image(self.window, self.cx, width, height, patch.bgra);
+        let (x, y) = patch.origin;
+        let bounds = bounds(
+            self.bounds.origin + point(self.length(x), self.length(y)),
+            size(self.length(width), self.length(height)),
+        );
+        self.clipped(|window, _| window.paint_image(bounds, Corners::default(), image, 0, false))
+            .transpose()
+            .map_err(|err| Error::Paint(err.to_string()))?;
+        Ok(())
+    }
+}
+
+impl GpuiBackend<'_> {
+    /// The gpui font for a plotters text style, falling back to the family of the window
+    /// text style when the requested one is not installed
+    fn font<TStyle: BackendTextStyle>(&self, style: &TStyle) -> Result<Font, Error> {
+        let base = self.window.text_style().font();
+        let base_family = base.family.clone();
+        let font = FontAliases::current(self.cx).font(style.family(), style.style(), base);
+
+        let text_system = self.window.text_system();
+        if text_system.font_id(&font).is_ok() {
+            return Ok(font);
+        }
+        warn!(
+            "font family {} not found, falling back to {}",
+            font.family, base_family
+        );
+        let fallback = Font {
+            family: base_family,
+            ..font
+        };
+        text_system
+            .font_id(&fallback)
+            .map_err(|err| Error::FontLookup(format!("{}: {}", fallback.family, err)))?;
+        Ok(fallback)
+    }
+
+    /// Shape a single line of text with the gpui text system, the layout carries the exact
+    /// width, ascent and descent used for anchoring
+    fn shape_text<TStyle: BackendTextStyle>(
+        &self,
+        text: &str,
+        style: &TStyle,
+    ) -> Result<ShapedLine, DrawingErrorKind<Error>> {
+        let run = TextRun {
+            len: text.len(),
+            font: self.font(style)?,
+            color: color_to_hsla(style.color()),
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+        };
+        self.window
+            .text_system()
+            .shape_line(
+                SharedString::from(text.to_string()),
+                self.length(style.size()),
+                &[run],
+            )
+            .map_err(|err| Error::TextShaping(err.to_string()).into())
+    }
+
+    /// Draw a pixel without recording it, rotated text is rasterized into pixels
+    fn paint_pixel(
+        &mut self,
+        point: BackendCoord,
+        color: BackendColor,
+    ) -> Result<(), DrawingErrorKind<Error>> {
+        self.flush_lines()?;
+        if !self.immediate_pixels {
+            self.pixels.blend(point, color);
+            return Ok(());
+        }
+
+        let color = color_to_hsla(color);
+        let point = self.point(point);
+        let size = Size::new(self.length(1), self.length(1));
+        let bounds = bounds(point, size);
+        let quad = fill(bounds, color);
+        self.clipped(|window, _| window.paint_quad(quad));
+
+        Ok(())
+    }
+
+    /// Shape every line of `text`, along with the line height of the block: the largest
+    /// ascent plus descent of the font among the lines
+    fn shape_lines<TStyle: BackendTextStyle>(
+        &self,
+        text: &str,
+        style: &TStyle,
+    ) -> Result<(Vec<ShapedLine>, Pixels), DrawingErrorKind<Error>> {
+        let lines = text_lines(text)
+            .map(|line| self.shape_text(line, style))
+            .collect::<Result<Vec<_>, _>>()?;
+        let line_height = lines
+            .iter()
+            .map(|line| (line.ascent + line.descent).0)
+            .fold(0.0, f32::max);
+        Ok((lines, px(line_height)))
+    }
+
+    /// Width, ascent and descent of `text` as laid out by gpui, in backend pixels. For
+    /// multi-line text the width is that of the widest line, the ascent that of the first line
+    /// and the descent reaches down to the bottom of the last line.
+    pub fn text_metrics<TStyle: BackendTextStyle>(
+        &self,
+        text: &str,
+        style: &TStyle,
+    ) -> Result<TextMetrics, DrawingErrorKind<Error>> {
+        let (lines, line_height) = self.shape_lines(text, style)?;
+        let width = lines.iter().map(|line| line.width.0).fold(0.0, f32::max);
+        let height = line_height * lines.len() as f32;
+        // gpui centres the glyphs in the line height, as with CSS half-leading
+        let first = &lines[0];
+        let ascent = (line_height - first.ascent - first.descent) / 2.0 + first.ascent;
+        Ok(TextMetrics {
+            width: width * self.scale,
+            ascent: ascent.0 * self.scale,
+            descent: (height - ascent).0 * self.scale,
+        })
+    }
+
+    /// gpui can only paint upright glyphs, so rotated text is rasterized by the font
+    /// backend of plotters, the same way the bitmap backend renders it. The anchor is resolved
+    /// on the unrotated layout boxes of the lines and rotated around `pos`.
+    ///
+    /// Every text gets an image of its own, just covering its glyphs. plotters draws a tick
+    /// line next to every label, so labels going through the shared pixel batch would flush
+    /// it once per label anyway.
+    fn draw_rotated_text<TStyle: BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &TStyle,
+        pos: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<Error>> {
+        let layouts = text_lines(text)
+            .map(|line| style.layout_box(line).map(|layout| (line, layout)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| DrawingErrorKind::FontError(Box::new(e)))?;
+        let line_height = layouts
+            .iter()
+            .map(|(_, ((_, min_y), (_, max_y)))| max_y - min_y)
+            .max()
+            .unwrap_or(0);
+        let height = line_height * layouts.len() as i32;
+        let top = match style.anchor().v_pos {
+            VPos::Top => 0,
+            VPos::Center => -height / 2,
+            VPos::Bottom => -height,
+        };
+        let transform = style.transform();
+
+        let mut pixels = vec![];
+        for (index, (line, ((min_x, min_y), (max_x, _)))) in layouts.into_iter().enumerate() {
+            let width = max_x - min_x;
+            let dx = match style.anchor().h_pos {
+                HPos::Left => 0,
+                HPos::Right => -width,
+                HPos::Center => -width / 2,
+            };
+            let dy = top + line_height * index as i32;
+            let result = style.draw(line, (0, 0), |x, y, color| {
+                let (x, y) = transform.transform(x + dx - min_x, y + dy - min_y);
+                if color.alpha > 0.0 {
+                    pixels.push(((pos.0 + x, pos.1 + y), color));
+                }
+                Ok::<(), DrawingErrorKind<Error>>(())
+            });
+            match result {
+                Ok(result) => result?,
+                Err(err) => return Err(DrawingErrorKind::FontError(Box::new(err))),
+            }
+        }
+
+        if self.immediate_pixels {
+            for (point, color) in pixels {
+                self.paint_pixel(point, color)?;
+            }
+            return Ok(());
+        }
+        let xs = pixels.iter().map(|((x, _), _)| *x);
+        let ys = pixels.iter().map(|((_, y), _)| *y);
+        let (Some(x0), Some(x1), Some(y0), Some(y1)) =
+            (xs.clone().min(), xs.max(), ys.clone().min(), ys.max())
+        else {
+            return Ok(());
+        };
+        let mut raster = PixelRaster::new((x1 - x0 + 1) as u32, (y1 - y0 + 1) as u32);
+        for ((x, y), color) in pixels {
+            raster.blend((x - x0, y - y0), color);
+        }
+        let Some(mut patch) = raster.take() else {
+            return Ok(());
+        };
+        patch.origin = (x0 + patch.origin.0, y0 + patch.origin.1);
+        // keep the paint order with what is batched so far
+        self.flush_pending()?;
+        self.paint_patch(patch)
+    }
+}
+
+impl Drop for GpuiBackend<'_> {
+    fn drop(&mut self) {
+        if let Err(err) = self.flush_pending() {
+            warn!("failed to paint batched primitives: {}", err);
+        }
+    }
+}
+
+/// Drawing state beyond what [`DrawingBackend`] covers, so charts can use
+/// [`with_clip`](crate::clip::with_clip), [`with_stroke_style`](crate::stroke::with_stroke_style),
+/// [`with_fill_rule`](crate::fill::with_fill_rule), [`MultiPolygon`](crate::fill::MultiPolygon),
+/// [`with_gradient`](crate::gradient::with_gradient) and
+/// [`GradientFill`](crate::gradient::GradientFill) on any backend of this crate.
+///
+/// The state is kept on stacks, so every push must be matched by the pop of the same kind.
+/// The `with_*` helpers do that around a closure.
+pub trait BackendState: DrawingBackend<ErrorType = Error> {
+    /// Clip everything drawn from now on to the rectangle between two backend coordinates,
+    /// intersected with the current clip
+    fn push_clip(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<Error>>;
+
+    /// Restore the clip that was active before the last [`Self::push_clip`]
+    fn pop_clip(&mut self) -> Result<(), DrawingErrorKind<Error>>;
+
+    /// Use `style` for the lines, paths and rectangle outlines drawn from now on
+    fn push_stroke_style(&mut self, style: StrokeStyle) -> Result<(), DrawingErrorKind<Error>>;
+
+    /// Restore the stroke style that was active before the last [`Self::push_stroke_style`]
+    fn pop_stroke_style(&mut self) -> Result<(), DrawingErrorKind<Error>>;
+
+    /// Use `rule` for the polygons filled from now on, the default is [`FillRule::EvenOdd`]
+    fn push_fill_rule(&mut self, rule: FillRule) -> Result<(), DrawingErrorKind<Error>>;
+
+    /// Restore the fill rule that was active before the last [`Self::push_fill_rule`]
+    fn pop_fill_rule(&mut self) -> Result<(), DrawingErrorKind<Error>>;
+
+    /// Fill all `contours` as a single shape with the current fill rule, so contours inside
+    /// others can cut holes. `fill_polygon` is the single contour case.
+    fn fill_contours<S: BackendStyle>(
+        &mut self,
+        contours: Vec<Vec<BackendCoord>>,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Error>>;
+
+    /// Fill the rectangles and polygons drawn from now on with `gradient` instead of the
+    /// color of their style
+    fn push_gradient(&mut self, gradient: LinearGradient) -> Result<(), DrawingErrorKind<Error>>;
+
+    /// Restore the fill that was active before the last [`Self::push_gradient`]
+    fn pop_gradient(&mut self) -> Result<(), DrawingErrorKind<Error>>;
+}
+
+impl BackendState for GpuiBackend<'_> {
+    fn push_clip(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(|| DrawCommand::PushClip {
+            upper_left,
+            bottom_right,
+        });
+        self.flush_pending()?;
+        let rect = Bounds::from_corners(self.point(upper_left), self.point(bottom_right));
+        let clip = rect.intersect(&self.clip_bounds());
+        self.clips.push(clip);
+        Ok(())
+    }
+
+    fn pop_clip(&mut self) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(|| DrawCommand::PopClip);
+        self.flush_pending()?;
+        if self.clips.pop().is_none() {
+            warn!("pop_clip called without a matching push_clip");
+        }
+        Ok(())
+    }
+
+    fn push_stroke_style(&mut self, style: StrokeStyle) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(|| DrawCommand::PushStrokeStyle(style.clone()));
+        self.flush_pending()?;
+        self.stroke_styles.push(style);
+        Ok(())
+    }
+
+    fn pop_stroke_style(&mut self) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(|| DrawCommand::PopStrokeStyle);
+        self.flush_pending()?;
+        if self.stroke_styles.pop().is_none() {
+            warn!("pop_stroke_style called without a matching push_stroke_style");
+        }
+        Ok(())
+    }
+
+    fn push_fill_rule(&mut self, rule: FillRule) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(|| DrawCommand::PushFillRule(rule));
+        self.flush_pending()?;
+        self.fill_rules.push(rule);
+        Ok(())
+    }
+
+    fn pop_fill_rule(&mut self) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(|| DrawCommand::PopFillRule);
+        self.flush_pending()?;
+        if self.fill_rules.pop().is_none() {
+            warn!("pop_fill_rule called without a matching push_fill_rule");
+        }
+        Ok(())
+    }
+
+    fn fill_contours<S: BackendStyle>(
+        &mut self,
+        contours: Vec<Vec<BackendCoord>>,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(|| DrawCommand::MultiPolygon {
+            contours: contours.clone(),
+            color: Color::from(style.color()),
+        });
+        self.paint_contours(contours, style.color())
+    }
+
+    fn push_gradient(&mut self, gradient: LinearGradient) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(|| DrawCommand::PushGradient(gradient.clone()));
+        self.flush_pending()?;
+        self.gradients.push(gradient);
+        Ok(())
+    }
+
+    fn pop_gradient(&mut self) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(|| DrawCommand::PopGradient);
+        self.flush_pending()?;
+        if self.gradients.pop().is_none() {
+            warn!("pop_gradient called without a matching push_gradient");
+        }
+        Ok(())
     }
 }
 
 impl DrawingBackend for GpuiBackend<'_> {
-    type ErrorType = crate::Error;
+    type ErrorType = Error;
 
     fn get_size(&self) -> (u32, u32) {
         let size = self.bounds.size;
-        (size.width.0 as u32, size.height.0 as u32)
+        (
+            (size.width.0 * self.scale) as u32,
+            (size.height.0 * self.scale) as u32,
+        )
     }
 
     fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
@@ -33,7 +678,7 @@ impl DrawingBackend for GpuiBackend<'_> {
     }
 
     fn present(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
-        Ok(())
+        self.flush_pending()
     }
 
     fn draw_pixel(
@@ -41,14 +686,11 @@ impl DrawingBackend for GpuiBackend<'_> {
         point: BackendCoord,
         color: BackendColor,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
-        let color = color_to_hsla(color);
-        let point = coord_to_point(self.bounds.origin, point);
-        let size = Size::new(Pixels(1.0), Pixels(1.0));
-        let bounds = bounds(point, size);
-        let quad = fill(bounds, color);
-        self.window.paint_quad(quad);
-
-        Ok(())
+        self.record(|| DrawCommand::Pixel {
+            point,
+            color: color.into(),
+        });
+        self.paint_pixel(point, color)
     }
 
     fn draw_line<S: BackendStyle>(
@@ -57,14 +699,23 @@ impl DrawingBackend for GpuiBackend<'_> {
         to: BackendCoord,
         style: &S,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
-        let mut line = Line::between_points(
-            coord_to_point(self.bounds.origin, from),
-            coord_to_point(self.bounds.origin, to),
-        )
-        .width(px(style.stroke_width() as _))
-        .color(color_to_hsla(style.color()));
+        self.record(|| DrawCommand::Line {
+            from,
+            to,
+            stroke: Stroke::of(style),
+        });
+        self.flush_pixels()?;
+        let width = style.stroke_width();
+        let points = vec![self.stroke_point(from, width), self.stroke_point(to, width)];
+        let line = self.line(points, style);
 
-        line.render_pixels(self.window);
+        // stroke styles only change between batches, see push_stroke_style
+        if let Some(last) = self.line_batch.last() {
+            if last.color != line.color || last.width != line.width {
+                self.flush_lines()?;
+            }
+        }
+        self.line_batch.push(line);
         Ok(())
     }
 
@@ -75,35 +726,43 @@ impl DrawingBackend for GpuiBackend<'_> {
         style: &S,
         fill: bool,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
-        let upper_left = coord_to_point(self.bounds.origin, upper_left);
-        let bottom_right = coord_to_point(self.bounds.origin, bottom_right);
-        let color = color_to_hsla(style.color());
+        self.record(|| DrawCommand::Rect {
+            upper_left,
+            bottom_right,
+            stroke: Stroke::of(style),
+            fill,
+        });
+        self.flush_pending()?;
 
-        if fill {
-            let mut builder = gpui::PathBuilder::fill();
-            builder.move_to(upper_left);
-            builder.line_to(point(upper_left.x, bottom_right.y));
-            builder.line_to(bottom_right);
-            builder.line_to(point(bottom_right.x, upper_left.y));
-            builder.line_to(upper_left);
-            let path = builder.build().map_err(|err| {
-                DrawingErrorKind::DrawingError(std::io::Error::other(err.to_string()))
-            })?;
-
-            self.window.paint_path(path, color);
-        } else {
-            for (p1, p2) in [
-                (upper_left, point(bottom_right.x, upper_left.y)),
-                (point(bottom_right.x, upper_left.y), bottom_right),
-                (bottom_right, point(upper_left.x, bottom_right.y)),
-                (point(upper_left.x, bottom_right.y), upper_left),
-            ] {
-                Line::between_points(p1, p2)
-                    .color(color)
-                    .render_pixels(self.window);
-            }
+        if !fill {
+            let width = style.stroke_width();
+            let upper_left = self.stroke_point(upper_left, width);
+            let bottom_right = self.stroke_point(bottom_right, width);
+            let points = vec![
+                upper_left,
+                point(upper_left.x, bottom_right.y),
+                bottom_right,
+                point(bottom_right.x, upper_left.y),
+            ];
+            // one closed contour, so the stroke gets proper joins at the corners
+            let mut line = self.line(points, style).closed(true);
+            self.clipped(|window, _| line.render_pixels(window));
+            return Ok(());
         }
 
+        let upper_left = self.point(upper_left);
+        let bottom_right = self.point(bottom_right);
+        let mut builder = PathBuilder::fill();
+        builder.move_to(upper_left);
+        builder.line_to(point(upper_left.x, bottom_right.y));
+        builder.line_to(bottom_right);
+        builder.line_to(point(bottom_right.x, upper_left.y));
+        builder.close();
+        let path = builder
+            .build()
+            .map_err(|err| Error::PathBuild(err.to_string()))?;
+        self.paint_fill(path, &[upper_left, bottom_right], style.color());
+
         Ok(())
     }
 
@@ -113,21 +772,73 @@ impl DrawingBackend for GpuiBackend<'_> {
         path: I,
         style: &S,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
-        let iter = path.into_iter();
-        let mut points = Vec::with_capacity(iter.size_hint().0 * 2);
-        for point in iter {
-            points.push(coord_to_point(self.bounds.origin, point));
+        let path: Vec<BackendCoord> = path.into_iter().collect();
+        self.record(|| DrawCommand::Path {
+            points: path.clone(),
+            stroke: Stroke::of(style),
+        });
+        self.flush_pending()?;
+        let width = style.stroke_width();
+        let mut points = Vec::with_capacity(path.len());
+        for point in path {
+            points.push(self.stroke_point(point, width));
         }
 
         if points.is_empty() {
             return Ok(());
         }
 
-        let mut line = Line::new();
-        line.points = points;
-        line.width = px(style.stroke_width() as _);
-        line.color = color_to_hsla(style.color());
-        line.render_pixels(self.window);
+        let mut line = self.line(points, style);
+        self.clipped(|window, _| line.render_pixels(window));
+
+        Ok(())
+    }
+
+    fn draw_circle<S: BackendStyle>(
+        &mut self,
+        center: BackendCoord,
+        radius: u32,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(|| DrawCommand::Circle {
+            center,
+            radius,
+            stroke: Stroke::of(style),
+            fill,
+        });
+        if style.color().alpha == 0.0 {
+            return Ok(());
+        }
+        if radius == 0 {
+            return self.paint_pixel(center, style.color());
+        }
+        self.flush_pending()?;
+
+        let center = self.point(center);
+        let radius = self.length(radius);
+        let color = color_to_hsla(style.color());
+
+        if fill {
+            let bounds = Bounds::centered_at(center, size(radius * 2.0, radius * 2.0));
+            let quad = gpui::fill(bounds, color).corner_radii(Corners::all(radius));
+            self.clipped(|window, _| window.paint_quad(quad));
+        } else {
+            // two half arcs, a single arc_to can't describe a full circle
+            let left = point(center.x - radius, center.y);
+            let right = point(center.x + radius, center.y);
+            let radii = point(radius, radius);
+            let mut builder = PathBuilder::stroke(self.length(style.stroke_width()));
+            builder.move_to(left);
+            builder.arc_to(radii, px(0.0), false, true, right);
+            builder.arc_to(radii, px(0.0), false, true, left);
+            builder.close();
+            let path = builder
+                .build()
+                .map_err(|err| Error::PathBuild(err.to_string()))?;
+
+            self.clipped(|window, _| window.paint_path(path, color));
+        }
 
         Ok(())
     }
@@ -137,77 +848,100 @@ impl DrawingBackend for GpuiBackend<'_> {
         vert: I,
         style: &S,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
-        let mut iter = vert.into_iter();
-        let start = match iter.next() {
-            Some(start) => start,
-            None => return Ok(()),
-        };
+        let vert: Vec<BackendCoord> = vert.into_iter().collect();
+        self.record(|| DrawCommand::Polygon {
+            points: vert.clone(),
+            color: Color::from(style.color()),
+        });
+        self.paint_contours(vec![vert], style.color())
+    }
 
-        let mut builder = gpui::PathBuilder::fill();
-        builder.move_to(coord_to_point(self.bounds.origin, start));
-        for point in iter {
-            builder.line_to(coord_to_point(self.bounds.origin, point));
+    fn blit_bitmap(
+        &mut self,
+        pos: BackendCoord,
+        (iw, ih): (u32, u32),
+        src: &[u8],
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(|| DrawCommand::Bitmap {
+            pos,
+            width: iw,
+            height: ih,
+            rgb: src.to_vec(),
+        });
+        if iw == 0 || ih == 0 {
+            return Ok(());
         }
+        rgb_pixels(iw, ih, src)?;
 
-        let path = builder.build().map_err(|err| {
-            DrawingErrorKind::DrawingError(std::io::Error::other(err.to_string()))
-        })?;
+        self.flush_pending()?;
+        if !self.paint {
+            return Ok(());
+        }
+        let image = BitmapCache::rgb_image(self.window, self.cx, iw, ih, src)?;
+        let bounds = bounds(self.point(pos), size(self.length(iw), self.length(ih)));
+        self.clipped(|window, _| window.paint_image(bounds, Corners::default(), image, 0, false))
+            .transpose()
+            .map_err(|err| Error::Paint(err.to_string()))?;
 
-        let color = color_to_hsla(style.color());
-        self.window.paint_path(path, color);
         Ok(())
     }
 
+    fn estimate_text_size<TStyle: BackendTextStyle>(
+        &self,
+        text: &str,
+        style: &TStyle,
+    ) -> Result<(u32, u32), DrawingErrorKind<Self::ErrorType>> {
+        let metrics = self.text_metrics(text, style)?;
+        Ok((metrics.width.ceil() as u32, metrics.height().ceil() as u32))
+    }
+
     fn draw_text<TStyle: BackendTextStyle>(
         &mut self,
         text: &str,
         style: &TStyle,
         pos: BackendCoord,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
-        let layout = style
-            .layout_box(text)
-            .map_err(|e| DrawingErrorKind::FontError(Box::new(e)))?;
-        let ((min_x, min_y), (max_x, max_y)) = layout;
-        let width = max_x - min_x;
-        let height = max_y - min_y;
-        let dx = match style.anchor().h_pos {
-            HPos::Left => 0,
-            HPos::Right => -width,
-            HPos::Center => -width / 2,
-        };
-        let dy = match style.anchor().v_pos {
-            VPos::Top => 0,
-            VPos::Center => -height / 2,
+        if self.recording.is_some() {
+            let metrics = self.text_metrics(text, style).ok();
+            let mut recorded = RecordedTextStyle::of(style);
+            recorded.font = self.font(style).ok().map(|font| font.family.to_string());
+            self.record(|| DrawCommand::Text {
+                text: text.to_string(),
+                pos,
+                style: recorded,
+                metrics,
+            });
+        }
+        if style.color().alpha == 0.0 {
+            return Ok(());
+        }
+        if !matches!(style.transform(), FontTransform::None) {
+            return self.draw_rotated_text(text, style, pos);
+        }
+
+        self.flush_pending()?;
+        let (lines, line_height) = self.shape_lines(text, style)?;
+        let height = line_height * lines.len() as f32;
+        let top = match style.anchor().v_pos {
+            VPos::Top => px(0.0),
+            VPos::Center => -height / 2.0,
             VPos::Bottom => -height,
         };
-        let color = color_to_hsla(style.color());
-        let point =
-            coord_to_point(self.bounds.origin, pos) + gpui::point(px(dx as _), px(dy as _)) * 1.24;
-        let font = self.window.text_style().font();
-        let len = text.len();
-        let size = px(style.size() as _);
-
-        let shaped_line = self
-            .window
-            .text_system()
-            .shape_line(
-                SharedString::from(text.to_string()),
-                size,
-                &[TextRun {
-                    len,
-                    font,
-                    color,
-                    background_color: None,
-                    underline: None,
-                    strikethrough: None,
-                }],
-            )
-            .map_err(|err| DrawingErrorKind::FontError(err.to_string().into()))?;
-        shaped_line
-            .paint(point, size, self.window, self.cx)
-            .map_err(|err| {
-                DrawingErrorKind::DrawingError(std::io::Error::other(err.to_string()))
-            })?;
+
+        // every line is anchored on its own, like the lines of a block of text in CSS
+        for (index, line) in lines.iter().enumerate() {
+            let dx = match style.anchor().h_pos {
+                HPos::Left => px(0.0),
+                HPos::Right => -line.width,
+                HPos::Center => -line.width / 2.0,
+            };
+            let origin = self.point(pos) + point(dx, top + line_height * index as f32);
+            // with the line height of the font the top of the text is at `origin`, gpui centres
+            // lines shaped with a smaller fallback font in it
+            self.clipped(|window, cx| line.paint(origin, line_height, window, cx))
+                .transpose()
+                .map_err(|err| Error::Paint(err.to_string()))?;
+        }
 
         Ok(())
     }
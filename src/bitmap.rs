@@ -0,0 +1,147 @@
+use crate::Error;
+use gpui::{App, Global, RenderImage, Window};
+use image::{Frame, RgbaImage};
+use smallvec::smallvec;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// How many uploaded bitmaps are kept alive before the oldest one is dropped from the atlas
+const BITMAP_CACHE_CAPACITY: usize = 64;
+
+/// Content cache of bitmaps uploaded to gpui, keyed by the hash of the pixel buffer.
+///
+/// Charts usually blit the same buffer on every frame, so the upload only happens
+/// once per distinct buffer.
+#[derive(Default)]
+pub struct BitmapCache {
+    images: HashMap<u64, Arc<RenderImage>>,
+    order: VecDeque<u64>,
+    /// evicted images, dropped from the atlas once the frame that may still paint them is
+    /// rendered
+    retired: Vec<Arc<RenderImage>>,
+}
+
+impl Global for BitmapCache {}
+
+impl BitmapCache {
+    /// Get the gpui image of a `width` x `height` RGB buffer, uploading it on a cache miss.
+    /// Plotters may hand over a longer buffer than the image, the rest is ignored.
+    pub fn rgb_image(
+        window: &mut Window,
+        cx: &mut App,
+        width: u32,
+        height: u32,
+        rgb: &[u8],
+    ) -> Result<Arc<RenderImage>, Error> {
+        let rgb = rgb_pixels(width, height, rgb)?;
+        let key = hash_bitmap(width, height, rgb);
+        Ok(Self::get_or_insert(window, cx, key, || {
+            let mut bgra = Vec::with_capacity(width as usize * height as usize * 4);
+            for pixel in rgb.chunks_exact(3) {
+                bgra.extend_from_slice(&[pixel[2], pixel[1], pixel[0], 255]);
+            }
+            bgra_to_render_image(width, height, bgra)
+        }))
+    }
+
+    /// Get the gpui image of a `width` x `height` BGRA buffer, uploading it on a cache miss
+    pub fn bgra_image(
+        window: &mut Window,
+        cx: &mut App,
+        width: u32,
+        height: u32,
+        bgra: Vec<u8>,
+    ) -> Arc<RenderImage> {
+        let key = hash_bitmap(width, height, &bgra);
+        Self::get_or_insert(window, cx, key, || {
+            bgra_to_render_image(width, height, bgra)
+        })
+    }
+
+    fn get_or_insert(
+        window: &mut Window,
+        cx: &mut App,
+        key: u64,
+        create: impl FnOnce() -> RenderImage,
+    ) -> Arc<RenderImage> {
+        let cache = cx.default_global::<BitmapCache>();
+        if let Some(image) = cache.images.get(&key) {
+            return image.clone();
+        }
+
+        let image = Arc::new(create());
+        cache.images.insert(key, image.clone());
+        cache.order.push_back(key);
+
+        let retired = cache.retired.len();
+        while cache.order.len() > BITMAP_CACHE_CAPACITY {
+            let Some(key) = cache.order.pop_front() else {
+                break;
+            };
+            if let Some(evicted) = cache.images.remove(&key) {
+                cache.retired.push(evicted);
+            }
+        }
+        // the first image retired in this frame schedules the drop of all of them
+        if retired == 0 && !cache.retired.is_empty() {
+            window.on_next_frame(Self::drop_retired);
+        }
+
+        image
+    }
+
+    fn drop_retired(window: &mut Window, cx: &mut App) {
+        let retired = std::mem::take(&mut cx.default_global::<BitmapCache>().retired);
+        for image in retired {
+            // the image may not be in this window's atlas, which is fine
+            let _ = window.drop_image(image);
+        }
+    }
+}
+
+/// The pixels of a `width` x `height` RGB image at the start of `rgb`, plotters may hand over
+/// a longer buffer than the image
+pub(crate) fn rgb_pixels(width: u32, height: u32, rgb: &[u8]) -> Result<&[u8], Error> {
+    let len = width as usize * height as usize * 3;
+    rgb.get(..len).ok_or_else(|| {
+        Error::Paint(format!(
+            "bitmap buffer of {} bytes is too small for {}x{} RGB",
+            rgb.len(),
+            width,
+            height
+        ))
+    })
+}
+
+fn hash_bitmap(width: u32, height: u32, data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    width.hash(&mut hasher);
+    height.hash(&mut hasher);
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// gpui expects BGRA frames
+fn bgra_to_render_image(width: u32, height: u32, bgra: Vec<u8>) -> RenderImage {
+    let buffer =
+        RgbaImage::from_raw(width, height, bgra).expect("buffer size is checked by the caller");
+    RenderImage::new(smallvec![Frame::new(buffer)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgb_pixels_ignores_trailing_bytes() {
+        let rgb = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+        assert_eq!(rgb_pixels(2, 1, &rgb).unwrap(), &[1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn rgb_pixels_rejects_short_buffers() {
+        assert!(matches!(rgb_pixels(2, 2, &[0; 11]), Err(Error::Paint(_))));
+    }
+}
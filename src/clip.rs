@@ -0,0 +1,25 @@
+use crate::backend::BackendState;
+use crate::command::{with_command, BackendCommand};
+use plotters::coord::CoordTranslate;
+use plotters::drawing::{DrawingArea, DrawingAreaErrorKind};
+
+/// Clip everything `draw` paints to the pixel extent of `area`.
+///
+/// The backend always clips to its own bounds, this narrows it down further, e.g. to keep
+/// series inside the plotting area when the axis ranges are smaller than the data:
+///
+/// ```ignore
+/// with_clip(chart.plotting_area(), || chart.draw_series(LineSeries::new(data, &RED)))??;
+/// ```
+pub fn with_clip<DB: BackendState, CT: CoordTranslate, R>(
+    area: &DrawingArea<DB, CT>,
+    draw: impl FnOnce() -> R,
+) -> Result<R, DrawingAreaErrorKind<crate::Error>> {
+    let (width, height) = area.dim_in_pixel();
+    with_command(
+        area,
+        BackendCommand::PushClip(width, height),
+        BackendCommand::PopClip,
+        draw,
+    )
+}
@@ -0,0 +1,75 @@
+use crate::backend::BackendState;
+use crate::fill::FillRule;
+use crate::gradient::LinearGradient;
+use crate::line::StrokeStyle;
+use plotters::coord::CoordTranslate;
+use plotters::drawing::{DrawingArea, DrawingAreaErrorKind};
+use plotters::element::{Drawable, PointCollection};
+use plotters_backend::{BackendCoord, DrawingErrorKind};
+
+/// Element without any visible output that gives access to the state of the backend behind a
+/// drawing area, since plotters only hands the backend out to elements being drawn
+pub(crate) enum BackendCommand {
+    /// Push a clip covering a drawing area of the given size
+    PushClip(u32, u32),
+    PopClip,
+    PushStrokeStyle(StrokeStyle),
+    PopStrokeStyle,
+    PushFillRule(FillRule),
+    PopFillRule,
+    PushGradient(LinearGradient),
+    PopGradient,
+}
+
+/// Draw `push` on `area`, run `draw` and draw `pop`, the body of the `with_*` helpers
+pub(crate) fn with_command<DB: BackendState, CT: CoordTranslate, R>(
+    area: &DrawingArea<DB, CT>,
+    push: BackendCommand,
+    pop: BackendCommand,
+    draw: impl FnOnce() -> R,
+) -> Result<R, DrawingAreaErrorKind<crate::Error>> {
+    let area = area.strip_coord_spec();
+    area.draw(&push)?;
+    let result = draw();
+    area.draw(&pop)?;
+    Ok(result)
+}
+
+impl<'a> PointCollection<'a, BackendCoord> for &'a BackendCommand {
+    type Point = BackendCoord;
+    type IntoIter = Vec<BackendCoord>;
+
+    fn point_iter(self) -> Self::IntoIter {
+        match self {
+            BackendCommand::PushClip(width, height) => {
+                vec![(0, 0), (*width as i32, *height as i32)]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+impl<DB: BackendState> Drawable<DB> for BackendCommand {
+    fn draw<I: Iterator<Item = BackendCoord>>(
+        &self,
+        mut pos: I,
+        backend: &mut DB,
+        _: (u32, u32),
+    ) -> Result<(), DrawingErrorKind<crate::Error>> {
+        match self {
+            BackendCommand::PushClip(..) => match (pos.next(), pos.next()) {
+                (Some(upper_left), Some(bottom_right)) => {
+                    backend.push_clip(upper_left, bottom_right)
+                }
+                _ => Ok(()),
+            },
+            BackendCommand::PopClip => backend.pop_clip(),
+            BackendCommand::PushStrokeStyle(style) => backend.push_stroke_style(style.clone()),
+            BackendCommand::PopStrokeStyle => backend.pop_stroke_style(),
+            BackendCommand::PushFillRule(rule) => backend.push_fill_rule(*rule),
+            BackendCommand::PopFillRule => backend.pop_fill_rule(),
+            BackendCommand::PushGradient(gradient) => backend.push_gradient(gradient.clone()),
+            BackendCommand::PopGradient => backend.pop_gradient(),
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use plotters::chart::ChartContext;
+use plotters::coord::cartesian::Cartesian2d;
+use plotters::coord::combinators::{IntoLogRange, LogCoord, LogScalable};
+use plotters::coord::ranged1d::{Ranged, ReversibleRanged, ValueFormatter};
+use plotters_backend::DrawingBackend;
+use std::any::Any;
+use std::ops::Range;
+use std::rc::Rc;
+
+/// Backend coordinates are mapped with this many steps per pixel, so positions between pixels
+/// map to values between the ones of the pixels
+const SUBPIXEL: i32 = 256;
+
+/// Steps of the bisection inverting axes without a reverse mapping
+const BISECT_STEPS: usize = 64;
+
+/// Values compared between a log axis and the one rebuilt from its range
+const LOG_PROBES: usize = 16;
+
+type MapFn<V> = Box<dyn Fn(&V, (i32, i32)) -> i32>;
+type UnmapFn<V> = Box<dyn Fn(i32, (i32, i32)) -> Option<V>>;
+type FormatFn<V> = Box<dyn Fn(&V) -> String>;
+
+/// How values of one axis map to pixels and back, and how they are shown
+pub struct AxisCoords<V> {
+    map: MapFn<V>,
+    unmap: UnmapFn<V>,
+    format: FormatFn<V>,
+}
+
+impl<V: 'static> AxisCoords<V> {
+    /// An axis plotters can map back itself: numeric, date and time and discrete axes. Values
+    /// are formatted like the labels of the axis.
+    pub fn reversible<R>(axis: &R) -> Self
+    where
+        R: ReversibleRanged<ValueType = V> + ValueFormatter<V> + Clone + 'static,
+    {
+        let (a, b, c) = (axis.clone(), axis.clone(), axis.clone());
+        Self {
+            map: Box::new(move |value, limit| a.map(value, limit)),
+            unmap: Box::new(move |pixel, limit| b.unmap(pixel, limit)),
+            format: Box::new(move |value| c.format_ext(value)),
+        }
+    }
+
+    /// A log scaled axis, `None` if it was built with a zero point other than the default.
+    ///
+    /// plotters keeps the zero point private, so the axis is rebuilt from its range and checked
+    /// against the original. The base only changes where the labels go, not the mapping.
+    pub fn log(axis: &LogCoord<V>) -> Option<Self>
+    where
+        V: LogScalable,
+    {
+        let range = axis.range();
+        let (a, b): (LogCoord<V>, LogCoord<V>) = (
+            range.clone().log_scale().into(),
+            range.clone().log_scale().into(),
+        );
+        let (start, end) = (range.start.as_f64(), range.end.as_f64());
+        let limit = (0, 1 << 20);
+        let same = (0..=LOG_PROBES).all(|index| {
+            let value = V::from_f64(start + (end - start) * index as f64 / LOG_PROBES as f64);
+            a.map(&value, limit) == axis.map(&value, limit)
+        });
+        if !same {
+            return None;
+        }
+        Some(Self {
+            map: Box::new(move |value, limit| a.map(value, limit)),
+            unmap: Box::new(move |pixel, limit| {
+                // the mapping is monotonic, find the value of the pixel by bisection
+                let pixel_of = |value: f64| b.map(&V::from_f64(value), limit);
+                let (first, last) = (pixel_of(start), pixel_of(end));
+                if pixel < first.min(last) || pixel > first.max(last) {
+                    return None;
+                }
+                let rising = last >= first;
+                let (mut low, mut high) = (start, end);
+                for _ in 0..BISECT_STEPS {
+                    let middle = (low + high) / 2.0;
+                    if (pixel_of(middle) < pixel) == rising {
+                        low = middle;
+                    } else {
+                        high = middle;
+                    }
+                }
+                Some(V::from_f64((low + high) / 2.0))
+            }),
+            format: Box::new(|value| significant(value.as_f64())),
+        })
+    }
+
+    /// Format values with `format` instead
+    pub fn formatter(mut self, format: impl Fn(&V) -> String + 'static) -> Self {
+        self.format = Box::new(format);
+        self
+    }
+}
+
+/// `value` with four significant digits
+fn significant(value: f64) -> String {
+    let magnitude = if value == 0.0 {
+        0
+    } else {
+        value.abs().log10().floor() as i32
+    };
+    format!("{:.*}", (3 - magnitude).max(0) as usize, value)
+}
+
+struct Axes<X, Y> {
+    x: AxisCoords<X>,
+    y: AxisCoords<Y>,
+}
+
+impl<X, Y> Axes<X, Y> {
+    fn to_data(&self, (x, y): (i32, i32), limits: ((i32, i32), (i32, i32))) -> Option<(X, Y)> {
+        Some(((self.x.unmap)(x, limits.0)?, (self.y.unmap)(y, limits.1)?))
+    }
+}
+
+/// [`Axes`] with their value types erased
+trait AnyAxes {
+    fn as_any(&self) -> &dyn Any;
+
+    fn format(
+        &self,
+        point: (i32, i32),
+        limits: ((i32, i32), (i32, i32)),
+    ) -> Option<(String, String)>;
+}
+
+impl<X: 'static, Y: 'static> AnyAxes for Axes<X, Y> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn format(
+        &self,
+        point: (i32, i32),
+        limits: ((i32, i32), (i32, i32)),
+    ) -> Option<(String, String)> {
+        let (x, y) = self.to_data(point, limits)?;
+        Some(((self.x.format)(&x), (self.y.format)(&y)))
+    }
+}
+
+/// The mapping between the data and the pixels of a chart's plotting area, kept after the
+/// chart is drawn to translate mouse positions into data values and back.
+///
+/// Charts build it from their `ChartContext` in [`PlottersChart::plot`] and hand it out from
+/// [`PlottersChart::coords`]:
+///
+/// ```ignore
+/// let mut chart = ChartBuilder::on(root).build_cartesian_2d(0.0..100.0, 0.0..1.0).unwrap();
+/// self.coords = Some(ChartCoords::new(&chart));
+/// ```
+///
+/// Pixels are backend coordinates, use
+/// [`PlottersDrawAreaViewer::pixel_to_data`](crate::element::PlottersDrawAreaViewer::pixel_to_data)
+/// to map window positions.
+///
+/// [`PlottersChart::plot`]: crate::element::PlottersChart::plot
+/// [`PlottersChart::coords`]: crate::element::PlottersChart::coords
+#[derive(Clone)]
+pub struct ChartCoords {
+    area: (Range<i32>, Range<i32>),
+    axes: Rc<dyn AnyAxes>,
+}
+
+impl ChartCoords {
+    /// The coordinates of a chart whose axes plotters can map back, see
+    /// [`AxisCoords::reversible`]
+    pub fn new<DB, X, Y>(chart: &ChartContext<DB, Cartesian2d<X, Y>>) -> Self
+    where
+        DB: DrawingBackend,
+        X: ReversibleRanged + ValueFormatter<X::ValueType> + Clone + 'static,
+        Y: ReversibleRanged + ValueFormatter<Y::ValueType> + Clone + 'static,
+    {
+        let spec = chart.as_coord_spec();
+        Self::with_axes(
+            chart,
+            AxisCoords::reversible(spec.x_spec()),
+            AxisCoords::reversible(spec.y_spec()),
+        )
+    }
+
+    /// The coordinates of a chart with other axes, e.g. a log scaled y axis:
+    ///
+    /// ```ignore
+    /// let spec = chart.as_coord_spec();
+    /// ChartCoords::with_axes(
+    ///     &chart,
+    ///     AxisCoords::reversible(spec.x_spec()),
+    ///     AxisCoords::log(spec.y_spec()).expect("default zero point"),
+    /// )
+    /// ```
+    pub fn with_axes<DB, X, Y>(
+        chart: &ChartContext<DB, Cartesian2d<X, Y>>,
+        x: AxisCoords<X::ValueType>,
+        y: AxisCoords<Y::ValueType>,
+    ) -> Self
+    where
+        DB: DrawingBackend,
+        X: Ranged,
+        Y: Ranged,
+        X::ValueType: 'static,
+        Y::ValueType: 'static,
+    {
+        Self {
+            area: chart.plotting_area().get_pixel_range(),
+            axes: Rc::new(Axes { x, y }),
+        }
+    }
+
+    /// The plotting area in backend pixels
+    pub fn plotting_area(&self) -> (Range<i32>, Range<i32>) {
+        self.area.clone()
+    }
+
+    pub fn contains(&self, (x, y): (f32, f32)) -> bool {
+        let (xs, ys) = &self.area;
+        x >= xs.start as f32 && x < xs.end as f32 && y >= ys.start as f32 && y < ys.end as f32
+    }
+
+    /// The value at a point of the plotting area, `None` outside of it or if `X` and `Y` are
+    /// not the value types of the chart
+    pub fn to_data<X: 'static, Y: 'static>(&self, point: (f32, f32)) -> Option<(X, Y)> {
+        if !self.contains(point) {
+            return None;
+        }
+        let axes = self.axes.as_any().downcast_ref::<Axes<X, Y>>()?;
+        axes.to_data(subpixel(point), self.limits())
+    }
+
+    /// The value at a point of the plotting area formatted by the axes, whatever their value
+    /// types
+    pub fn format(&self, point: (f32, f32)) -> Option<(String, String)> {
+        if !self.contains(point) {
+            return None;
+        }
+        self.axes.format(subpixel(point), self.limits())
+    }
+
+    /// Where a value is drawn, `None` if `X` and `Y` are not the value types of the chart
+    pub fn to_pixel<X: 'static, Y: 'static>(&self, value: &(X, Y)) -> Option<(f32, f32)> {
+        let axes = self.axes.as_any().downcast_ref::<Axes<X, Y>>()?;
+        let (x_limit, y_limit) = self.limits();
+        Some((
+            (axes.x.map)(&value.0, x_limit) as f32 / SUBPIXEL as f32,
+            (axes.y.map)(&value.1, y_limit) as f32 / SUBPIXEL as f32,
+        ))
+    }
+
+    /// The pixel limits the chart builder hands to the axes, with y pointing up
+    fn limits(&self) -> ((i32, i32), (i32, i32)) {
+        let (x, y) = &self.area;
+        (
+            (x.start * SUBPIXEL, (x.end - 1) * SUBPIXEL),
+            ((y.end - 1) * SUBPIXEL, y.start * SUBPIXEL),
+        )
+    }
+}
+
+fn subpixel((x, y): (f32, f32)) -> (i32, i32) {
+    let scale = |v: f32| (v * SUBPIXEL as f32).round() as i32;
+    (scale(x), scale(y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::{DisplayList, RecordingBackend};
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use plotters::prelude::*;
+
+    const SIZE: (u32, u32) = (400, 300);
+
+    fn builder<'a, 'b, DB: DrawingBackend>(
+        root: &'a DrawingArea<DB, plotters::coord::Shift>,
+    ) -> ChartBuilder<'a, 'b, DB> {
+        let mut builder = ChartBuilder::on(root);
+        builder
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(40);
+        builder
+    }
+
+    fn round_trip<X: 'static, Y: 'static>(coords: &ChartCoords, value: (X, Y)) -> (X, Y) {
+        let pixel = coords.to_pixel(&value).expect("value types of the chart");
+        coords
+            .to_data(pixel)
+            .expect("pixel inside the plotting area")
+    }
+
+    #[test]
+    fn linear_axes_round_trip() {
+        let mut list = DisplayList::default();
+        let root = RecordingBackend::new(&mut list, SIZE).into_drawing_area();
+        let chart = builder(&root)
+            .build_cartesian_2d(0.0..100.0, -1.0..1.0)
+            .unwrap();
+        let coords = ChartCoords::new(&chart);
+        for value in [(0.0, -1.0), (12.5, 0.0), (50.0, 0.3), (100.0, 1.0)] {
+            let (x, y): (f64, f64) = round_trip(&coords, value);
+            assert!((x - value.0).abs() < 1e-2, "{} != {}", x, value.0);
+            assert!((y - value.1).abs() < 1e-4, "{} != {}", y, value.1);
+        }
+    }
+
+    #[test]
+    fn log_axis_round_trips() {
+        let mut list = DisplayList::default();
+        let root = RecordingBackend::new(&mut list, SIZE).into_drawing_area();
+        let chart = builder(&root)
+            .build_cartesian_2d(0.0..10.0, (1.0..1000.0).log_scale().base(2.0))
+            .unwrap();
+        let spec = chart.as_coord_spec();
+        let coords = ChartCoords::with_axes(
+            &chart,
+            AxisCoords::reversible(spec.x_spec()),
+            AxisCoords::log(spec.y_spec()).unwrap(),
+        );
+        for y in [1.0, 3.0, 10.0, 420.0, 1000.0] {
+            let (_, y2): (f64, f64) = round_trip(&coords, (5.0, y));
+            assert!((y2 / y - 1.0).abs() < 1e-3, "{} != {}", y2, y);
+        }
+    }
+
+    #[test]
+    fn log_axis_with_zero_point_is_rejected() {
+        let axis: LogCoord<f64> = (1.0..1000.0).log_scale().zero_point(0.5).into();
+        assert!(AxisCoords::log(&axis).is_none());
+    }
+
+    #[test]
+    fn date_axis_round_trips() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let end = start + Duration::days(10);
+        let mut list = DisplayList::default();
+        let root = RecordingBackend::new(&mut list, SIZE).into_drawing_area();
+        let chart = builder(&root)
+            .build_cartesian_2d(start..end, 0.0..1.0)
+            .unwrap();
+        let coords = ChartCoords::new(&chart);
+        for hours in [0, 7, 100, 240] {
+            let date = start + Duration::hours(hours);
+            let (x, _): (DateTime<Utc>, f64) = round_trip(&coords, (date, 0.5));
+            assert!((x - date).num_seconds().abs() < 60, "{} != {}", x, date);
+        }
+    }
+
+    #[test]
+    fn outside_of_the_plotting_area() {
+        let mut list = DisplayList::default();
+        let root = RecordingBackend::new(&mut list, SIZE).into_drawing_area();
+        let chart = builder(&root)
+            .build_cartesian_2d(0.0..100.0, -1.0..1.0)
+            .unwrap();
+        let coords = ChartCoords::new(&chart);
+        let (xs, ys) = coords.plotting_area();
+        let inside = (xs.start as f32, ys.start as f32);
+        assert!(coords.to_data::<f64, f64>(inside).is_some());
+        for point in [
+            (0.0, 0.0),
+            (xs.start as f32 - 1.0, inside.1),
+            (xs.end as f32, inside.1),
+            (inside.0, ys.end as f32),
+        ] {
+            assert_eq!(coords.to_data::<f64, f64>(point), None);
+            assert_eq!(coords.format(point), None);
+        }
+        // not the value types of the chart
+        assert_eq!(coords.to_data::<i32, f64>(inside), None);
+    }
+}
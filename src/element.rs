@@ -1,94 +1,631 @@
 use crate::backend::GpuiBackend;
-use gpui::{canvas, App, Bounds, Context, IntoElement, Pixels, Render, Styled, Window};
+use crate::coords::ChartCoords;
+use crate::record::DisplayList;
+use crate::tooltip::{DataPoint, DataPoints, PointIndex};
+use crate::view::ViewRange;
+use gpui::{
+    canvas, div, point, px, rgb, App, Bounds, Context, FontWeight, InteractiveElement, IntoElement,
+    MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement, Pixels, Point,
+    Render, ScrollWheelEvent, Size, StatefulInteractiveElement, Styled, Window,
+};
 use parking_lot::RwLock;
 use plotters::coord::Shift;
 use plotters::drawing::{DrawingArea, IntoDrawingArea};
 use plotters::prelude::*;
+use plotters_backend::DrawingErrorKind;
 use std::rc::Rc;
 use tracing::error;
 
 pub struct PlottersDrawAreaModel {
     pub backend_color: RGBColor,
-    pub chart: Box<dyn PlottersChart>,
+    /// Render in device pixels on HiDPI displays, see [`GpuiBackend::device_pixels`]
+    pub device_pixels: bool,
+    pub chart: Box<GpuiChart>,
+    /// The part of the chart that is shown, handed to [`PlottersChart::set_view`] before
+    /// every plot. Call [`Self::mark_dirty`] after changing it.
+    pub view: ViewRange,
+    /// Let viewers zoom with the mouse wheel, pan by dragging and reset the view with a
+    /// double click. Off by default, turn it on for charts that follow the view in
+    /// [`PlottersChart::set_view`]; otherwise wheel events go to the parent as usual.
+    ///
+    /// Zoom and pan follow the plotting area of charts that hand out [`PlottersChart::coords`].
+    /// Without them the plotting area is unknown and the whole element is used, so the point
+    /// under the cursor drifts by the share of the axis labels while zooming.
+    pub interactive: bool,
+    /// How close the mouse has to come to a point registered by the chart to show its tooltip,
+    /// see [`PlottersChart::data_points`]. Zero turns tooltips off.
+    pub tooltip_radius: Pixels,
+    /// Follow the mouse with guide lines across the plotting area and show the values under
+    /// it at the axes. Needs [`PlottersChart::coords`], and is drawn over the last plot
+    /// without running the chart again.
+    pub crosshair: bool,
+    revision: u64,
 }
 
 impl PlottersDrawAreaModel {
-    pub fn new(chart: Box<dyn PlottersChart>) -> Self {
+    pub fn new(chart: Box<GpuiChart>) -> Self {
         Self {
             backend_color: WHITE,
+            device_pixels: false,
             chart,
+            view: ViewRange::default(),
+            interactive: false,
+            tooltip_radius: px(8.0),
+            crosshair: false,
+            revision: 0,
         }
     }
+
+    /// Make viewers run the chart again on their next paint instead of repainting what it
+    /// drew last time. Call this whenever the chart or the data behind it changes.
+    pub fn mark_dirty(&mut self) {
+        self.revision = self.revision.wrapping_add(1);
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Run the chart at `size` without painting anything, e.g. to export it at another size
+    /// than it is shown at. Text is still laid out by gpui, so the result matches the screen.
+    pub fn record(
+        &mut self,
+        size: Size<Pixels>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Result<DisplayList, DrawingAreaErrorKind<crate::Error>> {
+        let mut list = DisplayList::default();
+        let backend = GpuiBackend::new(Bounds::new(Point::default(), size), window, cx)
+            .device_pixels(self.device_pixels)
+            .paint(false)
+            .record_into(&mut list);
+        self.draw(backend)?;
+        Ok(list)
+    }
+
+    /// Fill the background and run the chart on `backend`
+    fn draw(&mut self, backend: GpuiBackend) -> Result<(), DrawingAreaErrorKind<crate::Error>> {
+        let root = backend.into_drawing_area();
+        root.fill(&self.backend_color)?;
+        self.chart.set_view(self.view);
+        self.chart
+            .plot(&root)
+            .map_err(DrawingAreaErrorKind::BackendError)?;
+        root.present()?;
+        Ok(())
+    }
 }
+
+/// Everything the output of a plot depends on besides the model's revision
+#[derive(Clone, Copy, PartialEq)]
+struct PlotKey {
+    size: Size<Pixels>,
+    scale_factor: f32,
+    device_pixels: bool,
+    /// the background is filled before the chart runs, so it is part of the recording
+    backend_color: RGBColor,
+    revision: u64,
+}
+
+impl PlotKey {
+    /// Backend pixels per logical pixel
+    fn scale(&self) -> f32 {
+        if self.device_pixels {
+            self.scale_factor
+        } else {
+            1.0
+        }
+    }
+}
+
+/// The draw output of the last plot, replayed as long as its key stays the same
+struct PlotCache {
+    key: PlotKey,
+    list: DisplayList,
+    /// what the chart registered while drawing `list`
+    coords: Option<ChartCoords>,
+    points: Option<PointIndex>,
+}
+
+/// State of the mouse interaction with a viewer
+#[derive(Default)]
+struct Interaction {
+    /// where the chart was painted last, in window coordinates
+    bounds: Option<Bounds<Pixels>>,
+    /// last position of the mouse while dragging
+    drag: Option<Point<Pixels>>,
+    /// the point the tooltip is shown for
+    hover: Option<Hover>,
+    /// position of the mouse over the viewer, in window coordinates
+    cursor: Option<Point<Pixels>>,
+}
+
+/// The crosshair at the mouse, relative to the viewer
+struct Crosshair {
+    position: Point<Pixels>,
+    plotting_area: Bounds<Pixels>,
+    /// the formatted values under the mouse
+    x: String,
+    y: String,
+}
+
+#[derive(Clone, PartialEq)]
+struct Hover {
+    point: DataPoint,
+    /// where the point is drawn, relative to the viewer
+    position: Point<Pixels>,
+}
+
+/// Wheel zoom speed, the view shrinks by this factor per pixel scrolled
+const ZOOM_PER_PIXEL: f64 = 1.002;
+
 #[derive(Clone)]
 pub struct PlottersDrawAreaViewer {
     model: Rc<RwLock<PlottersDrawAreaModel>>,
+    cache: Rc<RwLock<Option<PlotCache>>>,
+    interaction: Rc<RwLock<Interaction>>,
 }
 
 impl PlottersDrawAreaViewer {
     pub fn new(model: PlottersDrawAreaModel) -> Self {
-        let model = Rc::new(RwLock::new(model));
-        Self { model }
+        Self::with_shared_model(Rc::new(RwLock::new(model)))
     }
 
     pub fn with_shared_model(model: Rc<RwLock<PlottersDrawAreaModel>>) -> Self {
-        Self { model }
+        Self {
+            model,
+            cache: Rc::new(RwLock::new(None)),
+            interaction: Rc::new(RwLock::new(Interaction::default())),
+        }
     }
 
+    /// Run the chart, or repaint what it drew last time if neither the model's revision nor
+    /// the size of the element changed
     pub fn plot(
         &self,
         bounds: Bounds<Pixels>,
         window: &mut Window,
         cx: &mut App,
     ) -> Result<(), DrawingAreaErrorKind<crate::Error>> {
+        self.interaction.write().bounds = Some(bounds);
         let mut model = self.model.write();
-        let root = GpuiBackend::new(bounds, window, cx).into_drawing_area();
-        root.fill(&model.backend_color)?;
-        model
-            .chart
-            .plot(&root)
-            .map_err(DrawingAreaErrorKind::BackendError)?;
-        root.present()?;
+        let mut cache = self.cache.write();
+        let key = PlotKey {
+            size: bounds.size,
+            scale_factor: window.scale_factor(),
+            device_pixels: model.device_pixels,
+            backend_color: model.backend_color,
+            revision: model.revision,
+        };
+
+        if let Some(cache) = cache.as_ref().filter(|cache| cache.key == key) {
+            let mut backend =
+                GpuiBackend::new(bounds, window, cx).device_pixels(model.device_pixels);
+            cache
+                .list
+                .replay(&mut backend)
+                .map_err(DrawingAreaErrorKind::BackendError)?;
+            backend
+                .present()
+                .map_err(DrawingAreaErrorKind::BackendError)?;
+            return Ok(());
+        }
+
+        let mut list = DisplayList::default();
+        let backend = GpuiBackend::new(bounds, window, cx)
+            .device_pixels(model.device_pixels)
+            .record_into(&mut list);
+        model.draw(backend)?;
+        let coords = model.chart.coords();
+        let points = model.chart.data_points().map(PointIndex::new);
+        *cache = Some(PlotCache {
+            key,
+            list,
+            coords,
+            points,
+        });
         Ok(())
     }
+
+    /// What the viewer painted last, e.g. to export the chart exactly as it is shown
+    pub fn display_list(&self) -> Option<DisplayList> {
+        self.cache.read().as_ref().map(|cache| cache.list.clone())
+    }
+
+    /// The coordinates the chart registered when it was drawn last, see [`PlottersChart::coords`]
+    pub fn coords(&self) -> Option<ChartCoords> {
+        self.cache.read().as_ref()?.coords.clone()
+    }
+
+    /// The data value under a point in window coordinates, e.g. the position of a mouse event.
+    /// `None` outside the plotting area, if the chart registered no coordinates or if `X` and
+    /// `Y` are not the value types of its axes.
+    pub fn pixel_to_data<X: 'static, Y: 'static>(&self, point: Point<Pixels>) -> Option<(X, Y)> {
+        let origin = self.interaction.read().bounds?.origin;
+        let cache = self.cache.read();
+        let cache = cache.as_ref()?;
+        let scale = cache.key.scale();
+        let point = point - origin;
+        cache
+            .coords
+            .as_ref()?
+            .to_data((point.x.0 * scale, point.y.0 * scale))
+    }
+
+    /// Where a data value is shown, in window coordinates
+    pub fn data_to_pixel<X: 'static, Y: 'static>(&self, value: &(X, Y)) -> Option<Point<Pixels>> {
+        let origin = self.interaction.read().bounds?.origin;
+        let cache = self.cache.read();
+        let cache = cache.as_ref()?;
+        let scale = cache.key.scale();
+        let (x, y) = cache.coords.as_ref()?.to_pixel(value)?;
+        Some(origin + point(px(x / scale), px(y / scale)))
+    }
+
+    /// The part of the element zoom and pan are relative to: the plotting area if the chart
+    /// registered its coordinates, the whole element otherwise
+    fn view_bounds(&self) -> Option<Bounds<Pixels>> {
+        let bounds = self.interaction.read().bounds?;
+        let cache = self.cache.read();
+        let Some((key, coords)) = cache
+            .as_ref()
+            .and_then(|cache| Some((cache.key, cache.coords.as_ref()?)))
+        else {
+            return Some(bounds);
+        };
+        let scale = key.scale();
+        let (x, y) = coords.plotting_area();
+        Some(Bounds::from_corners(
+            bounds.origin + point(px(x.start as f32 / scale), px(y.start as f32 / scale)),
+            bounds.origin + point(px(x.end as f32 / scale), px(y.end as f32 / scale)),
+        ))
+    }
+
+    /// The crosshair at the last position of the mouse, if it is inside the plotting area
+    fn crosshair(&self) -> Option<Crosshair> {
+        if !self.model.read().crosshair {
+            return None;
+        }
+        let (origin, cursor) = {
+            let interaction = self.interaction.read();
+            (interaction.bounds?.origin, interaction.cursor?)
+        };
+        let plotting_area = self.view_bounds()?;
+        let cache = self.cache.read();
+        let cache = cache.as_ref()?;
+        let scale = cache.key.scale();
+        let position = cursor - origin;
+        let (x, y) = cache
+            .coords
+            .as_ref()?
+            .format((position.x.0 * scale, position.y.0 * scale))?;
+        Some(Crosshair {
+            position,
+            plotting_area: Bounds::new(plotting_area.origin - origin, plotting_area.size),
+            x,
+            y,
+        })
+    }
+
+    fn set_cursor(&self, cursor: Option<Point<Pixels>>, cx: &mut Context<Self>) {
+        let crosshair = self.model.read().crosshair;
+        let mut interaction = self.interaction.write();
+        if interaction.cursor != cursor {
+            interaction.cursor = cursor;
+            // only the overlay changes, the plot is repainted from the cache
+            if crosshair {
+                cx.notify();
+            }
+        }
+    }
+
+    /// Change the view of an interactive model and plot it again
+    fn update_view(&self, cx: &mut Context<Self>, update: impl FnOnce(&mut ViewRange)) {
+        let mut model = self.model.write();
+        if !model.interactive {
+            return;
+        }
+        let view = model.view;
+        update(&mut model.view);
+        if model.view != view {
+            model.mark_dirty();
+            // the points move with the view, the tooltip would be left behind
+            self.interaction.write().hover = None;
+            cx.notify();
+        }
+    }
+
+    /// The registered point within the tooltip radius of `position`, in window coordinates
+    fn hit_test(&self, position: Point<Pixels>) -> Option<Hover> {
+        let radius = self.model.read().tooltip_radius;
+        if radius <= px(0.0) {
+            return None;
+        }
+        let origin = self.interaction.read().bounds?.origin;
+        let cache = self.cache.read();
+        let cache = cache.as_ref()?;
+        let scale = cache.key.scale();
+        let position = position - origin;
+        let point = cache.points.as_ref()?.nearest(
+            (position.x.0 * scale, position.y.0 * scale),
+            radius.0 * scale,
+        )?;
+        let (x, y) = point.position;
+        Some(Hover {
+            point: point.clone(),
+            position: gpui::point(px(x as f32 / scale), px(y as f32 / scale)),
+        })
+    }
+
+    fn set_hover(&self, hover: Option<Hover>, cx: &mut Context<Self>) {
+        let mut interaction = self.interaction.write();
+        if interaction.hover != hover {
+            interaction.hover = hover;
+            cx.notify();
+        }
+    }
+
+    fn on_scroll_wheel(
+        &mut self,
+        event: &ScrollWheelEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // let the parent scroll, the view can't change
+        if !self.model.read().interactive {
+            return;
+        }
+        let Some(bounds) = self.view_bounds() else {
+            return;
+        };
+        let delta = event.delta.pixel_delta(window.line_height());
+        // the point under the cursor stays in place, y grows upwards in the view
+        let position = event.position - bounds.origin;
+        let anchor = (
+            (position.x / bounds.size.width).clamp(0.0, 1.0) as f64,
+            1.0 - (position.y / bounds.size.height).clamp(0.0, 1.0) as f64,
+        );
+        let factor = ZOOM_PER_PIXEL.powf(delta.y.0 as f64);
+        self.update_view(cx, |view| view.zoom(factor, anchor));
+        cx.stop_propagation();
+    }
+
+    fn on_mouse_down(&mut self, event: &MouseDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.model.read().interactive {
+            return;
+        }
+        if event.click_count == 2 {
+            self.interaction.write().drag = None;
+            self.update_view(cx, ViewRange::reset);
+        } else {
+            self.interaction.write().drag = Some(event.position);
+        }
+    }
+
+    fn on_mouse_move(&mut self, event: &MouseMoveEvent, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(Some(event.position), cx);
+        let mut interaction = self.interaction.write();
+        let dragging = event.pressed_button == Some(MouseButton::Left);
+        let last = interaction.drag.filter(|_| dragging);
+        interaction.drag = last.map(|_| event.position);
+        drop(interaction);
+
+        let Some(last) = last else {
+            let hover = self.hit_test(event.position);
+            self.set_hover(hover, cx);
+            return;
+        };
+        self.set_hover(None, cx);
+        let Some(bounds) = self.view_bounds() else {
+            return;
+        };
+
+        // dragging moves the content with the cursor, so the view moves the other way
+        let delta = event.position - last;
+        if bounds.size.width > px(0.0) && bounds.size.height > px(0.0) {
+            let dx = -(delta.x / bounds.size.width) as f64;
+            let dy = (delta.y / bounds.size.height) as f64;
+            self.update_view(cx, |view| view.pan(dx, dy));
+        }
+    }
+
+    fn on_mouse_up(&mut self, _: &MouseUpEvent, _: &mut Window, _: &mut Context<Self>) {
+        self.interaction.write().drag = None;
+    }
+
+    fn on_hover(&mut self, hovered: &bool, _: &mut Window, cx: &mut Context<Self>) {
+        if !hovered {
+            self.set_hover(None, cx);
+            self.set_cursor(None, cx);
+        }
+    }
+}
+
+/// Guide lines across the plotting area and badges with the values at its axes
+fn crosshair(crosshair: Crosshair, size: Size<Pixels>) -> impl IntoElement {
+    let Crosshair {
+        position,
+        plotting_area: area,
+        x,
+        y,
+    } = crosshair;
+    let line = || div().absolute().bg(rgb(0x808080));
+    let badge = |text: String| {
+        div()
+            .absolute()
+            .px_1()
+            .bg(rgb(0x404040))
+            .rounded_sm()
+            .text_xs()
+            .text_color(gpui::white())
+            .whitespace_nowrap()
+            .child(text)
+    };
+    let x_badge = badge(x).top(area.bottom());
+    let x_badge = if position.x < size.width / 2.0 {
+        x_badge.left(position.x)
+    } else {
+        x_badge.right(size.width - position.x)
+    };
+    let y_badge = badge(y).right(size.width - area.left());
+    let y_badge = if position.y < size.height / 2.0 {
+        y_badge.top(position.y)
+    } else {
+        y_badge.bottom(size.height - position.y)
+    };
+    div()
+        .absolute()
+        .size_full()
+        .child(
+            line()
+                .left(position.x)
+                .top(area.top())
+                .w(px(1.0))
+                .h(area.size.height),
+        )
+        .child(
+            line()
+                .left(area.left())
+                .top(position.y)
+                .w(area.size.width)
+                .h(px(1.0)),
+        )
+        .child(x_badge)
+        .child(y_badge)
+}
+
+/// Gap between a point and its tooltip
+const TOOLTIP_OFFSET: Pixels = px(10.0);
+
+/// The tooltip of `hover`, placed on the side of the point facing the middle of the viewer
+/// so it stays inside
+fn tooltip(hover: &Hover, size: Size<Pixels>) -> impl IntoElement {
+    let Hover { point, position } = hover;
+    let tooltip = div()
+        .absolute()
+        .flex_col()
+        .px_2()
+        .py_1()
+        .bg(gpui::white())
+        .border_1()
+        .border_color(rgb(0xa0a0a0))
+        .rounded_md()
+        .shadow_md()
+        .text_xs()
+        .text_color(gpui::black())
+        .whitespace_nowrap()
+        .child(
+            div()
+                .font_weight(FontWeight::SEMIBOLD)
+                .child(point.series.clone()),
+        )
+        .child(format!("x: {}", point.x))
+        .child(format!("y: {}", point.y));
+    let tooltip = if position.x < size.width / 2.0 {
+        tooltip.left(position.x + TOOLTIP_OFFSET)
+    } else {
+        tooltip.right(size.width - position.x + TOOLTIP_OFFSET)
+    };
+    if position.y < size.height / 2.0 {
+        tooltip.top(position.y + TOOLTIP_OFFSET)
+    } else {
+        tooltip.bottom(size.height - position.y + TOOLTIP_OFFSET)
+    }
 }
 
 impl Render for PlottersDrawAreaViewer {
-    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let this = self.clone();
-        canvas(
-            |_, _, _| {},
-            move |bounds, _, window, cx| {
-                if let Err(err) = this.plot(bounds, window, cx) {
-                    error!("failed to plot: {}", err);
-                }
-            },
-        )
-        .size_full()
+        let crosshair = self.crosshair();
+        let interaction = self.interaction.read();
+        let tooltip = interaction
+            .hover
+            .as_ref()
+            .zip(interaction.bounds)
+            .map(|(hover, bounds)| tooltip(hover, bounds.size));
+        let crosshair = crosshair
+            .zip(interaction.bounds)
+            .map(|(crosshair, bounds)| self::crosshair(crosshair, bounds.size));
+        div()
+            .id("plotters-viewer")
+            .relative()
+            .size_full()
+            .on_scroll_wheel(cx.listener(Self::on_scroll_wheel))
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::on_mouse_down))
+            .on_mouse_move(cx.listener(Self::on_mouse_move))
+            .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up))
+            .on_mouse_up_out(MouseButton::Left, cx.listener(Self::on_mouse_up))
+            .on_hover(cx.listener(Self::on_hover))
+            .child(
+                canvas(
+                    |_, _, _| {},
+                    move |bounds, _, window, cx| {
+                        if let Err(err) = this.plot(bounds, window, cx) {
+                            error!("failed to plot: {}", err);
+                        }
+                    },
+                )
+                .size_full(),
+            )
+            .children(crosshair)
+            .children(tooltip)
     }
 }
 
-pub trait PlottersChart {
+/// A chart drawn by plotters on any backend, so the chart shown in a
+/// [`PlottersDrawAreaViewer`] can also be rendered with plotters' `SVGBackend` or
+/// `BitMapBackend`, or recorded for tests. Implement it for every backend the chart supports:
+///
+/// ```ignore
+/// impl<DB: DrawingBackend> PlottersChart<DB> for MyChart {
+///     fn plot(&mut self, root: &DrawingArea<DB, Shift>) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
+///         ...
+///     }
+/// }
+/// ```
+///
+/// Charts using the extensions of this crate, like [`with_clip`](crate::clip::with_clip),
+/// can be implemented for `DB: BackendState` instead.
+pub trait PlottersChart<DB: DrawingBackend> {
     fn plot(
         &mut self,
-        area: &DrawingArea<GpuiBackend, Shift>,
-    ) -> Result<(), crate::DrawingErrorKind>;
+        area: &DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingErrorKind<DB::ErrorType>>;
+
+    /// Called with the view of [`PlottersDrawAreaModel`] before every plot, charts supporting
+    /// zoom and pan keep it to build their coordinates from
+    fn set_view(&mut self, _view: ViewRange) {}
+
+    /// The mapping between data and pixels of the last plot, so viewers can translate mouse
+    /// positions into data values, see [`ChartCoords`]
+    fn coords(&self) -> Option<ChartCoords> {
+        None
+    }
+
+    /// The points of the last plot tooltips are shown for, see [`DataPoints`]
+    fn data_points(&self) -> Option<Rc<DataPoints>> {
+        None
+    }
 }
 
-impl PlottersChart for () {
-    fn plot(&mut self, _: &DrawingArea<GpuiBackend, Shift>) -> Result<(), crate::DrawingErrorKind> {
+/// A chart that can be shown by [`PlottersDrawAreaModel`]
+pub type GpuiChart = dyn for<'a> PlottersChart<GpuiBackend<'a>>;
+
+impl<DB: DrawingBackend> PlottersChart<DB> for () {
+    fn plot(&mut self, _: &DrawingArea<DB, Shift>) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
         Ok(())
     }
 }
 
-impl<F> PlottersChart for F
+impl<DB, F> PlottersChart<DB> for F
 where
-    F: FnMut(&DrawingArea<GpuiBackend, Shift>) -> Result<(), crate::DrawingErrorKind>,
+    DB: DrawingBackend,
+    F: FnMut(&DrawingArea<DB, Shift>) -> Result<(), DrawingErrorKind<DB::ErrorType>>,
 {
     fn plot(
         &mut self,
-        area: &DrawingArea<GpuiBackend, Shift>,
-    ) -> Result<(), crate::DrawingErrorKind> {
+        area: &DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
         self(area)
     }
 }
@@ -96,15 +633,31 @@ where
 macro_rules! impl_plotters_char_for_tuple {
     ($($name:ident),*) => {
         #[allow(non_snake_case)]
-        impl<$($name),*> PlottersChart for ($($name,)*)
+        impl<DB, $($name),*> PlottersChart<DB> for ($($name,)*)
         where
-            $($name: PlottersChart,)*
+            DB: DrawingBackend,
+            $($name: PlottersChart<DB>,)*
         {
-            fn plot(&mut self, area: &DrawingArea<GpuiBackend, Shift>) -> Result<(), crate::DrawingErrorKind> {
+            fn plot(&mut self, area: &DrawingArea<DB, Shift>) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
                 let ($($name,)*) = self;
                 $($name.plot(area)?;)*
                 Ok(())
             }
+
+            fn set_view(&mut self, view: ViewRange) {
+                let ($($name,)*) = self;
+                $($name.set_view(view);)*
+            }
+
+            fn coords(&self) -> Option<ChartCoords> {
+                let ($($name,)*) = self;
+                None$(.or_else(|| $name.coords()))*
+            }
+
+            fn data_points(&self) -> Option<Rc<DataPoints>> {
+                let ($($name,)*) = self;
+                None$(.or_else(|| $name.data_points()))*
+            }
         }
     };
 }
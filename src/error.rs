@@ -0,0 +1,68 @@
+use std::fmt;
+
+/// Errors raised while drawing a chart with plotters-gpui
+#[derive(Debug)]
+pub enum Error {
+    /// A path could not be built or tessellated by gpui
+    PathBuild(String),
+    /// gpui failed to shape a piece of text
+    TextShaping(String),
+    /// No usable font was found for the requested family
+    FontLookup(String),
+    /// gpui refused to paint a primitive, e.g. an image that doesn't fit in the atlas
+    Paint(String),
+    /// Writing an exported chart failed
+    Io(std::io::Error),
+    /// An error raised by the chart itself
+    Chart(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl Error {
+    /// Wrap an error of a chart implementation, so it can be returned from
+    /// [`PlottersChart::plot`](crate::element::PlottersChart::plot) with `?`
+    pub fn chart(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self::Chart(err.into())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::PathBuild(err) => write!(f, "failed to build path: {}", err),
+            Error::TextShaping(err) => write!(f, "failed to shape text: {}", err),
+            Error::FontLookup(err) => write!(f, "failed to find font: {}", err),
+            Error::Paint(err) => write!(f, "failed to paint: {}", err),
+            Error::Io(err) => write!(f, "io error: {}", err),
+            Error::Chart(err) => write!(f, "chart error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Chart(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<Error> for plotters_backend::DrawingErrorKind<Error> {
+    fn from(err: Error) -> Self {
+        plotters_backend::DrawingErrorKind::DrawingError(err)
+    }
+}
+
+#[cfg(feature = "plotters")]
+impl From<Error> for plotters::drawing::DrawingAreaErrorKind<Error> {
+    fn from(err: Error) -> Self {
+        plotters::drawing::DrawingAreaErrorKind::BackendError(err.into())
+    }
+}
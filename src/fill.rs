@@ -0,0 +1,115 @@
+#[cfg(feature = "plotters")]
+use crate::backend::BackendState;
+#[cfg(feature = "plotters")]
+use crate::command::{with_command, BackendCommand};
+use gpui::FillOptions;
+use lyon::tessellation::FillRule as LyonFillRule;
+#[cfg(feature = "plotters")]
+use plotters::coord::CoordTranslate;
+#[cfg(feature = "plotters")]
+use plotters::drawing::{DrawingArea, DrawingAreaErrorKind};
+#[cfg(feature = "plotters")]
+use plotters::element::{Drawable, PointCollection};
+#[cfg(feature = "plotters")]
+use plotters::style::ShapeStyle;
+#[cfg(feature = "plotters")]
+use plotters_backend::{BackendCoord, DrawingErrorKind};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Which parts of a self-intersecting polygon, or of a polygon with several contours, are
+/// filled by [`GpuiBackend`](crate::backend::GpuiBackend)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FillRule {
+    /// Fill where a ray from the point crosses the outline an odd number of times, so a
+    /// contour inside another one cuts a hole regardless of its direction
+    #[default]
+    EvenOdd,
+    /// Fill where the outline winds around the point at least once, a contour only cuts a
+    /// hole if it runs in the opposite direction
+    NonZero,
+}
+
+impl FillRule {
+    pub(crate) fn fill_options(self) -> FillOptions {
+        FillOptions::default().with_fill_rule(match self {
+            FillRule::EvenOdd => LyonFillRule::EvenOdd,
+            FillRule::NonZero => LyonFillRule::NonZero,
+        })
+    }
+}
+
+/// Fill everything `draw` fills with `rule`.
+///
+/// ```ignore
+/// with_fill_rule(root, FillRule::NonZero, || {
+///     chart.draw_series(AreaSeries::new(data, 0.0, BLUE.mix(0.3)))
+/// })??;
+/// ```
+#[cfg(feature = "plotters")]
+pub fn with_fill_rule<DB: BackendState, CT: CoordTranslate, R>(
+    area: &DrawingArea<DB, CT>,
+    rule: FillRule,
+    draw: impl FnOnce() -> R,
+) -> Result<R, DrawingAreaErrorKind<crate::Error>> {
+    with_command(
+        area,
+        BackendCommand::PushFillRule(rule),
+        BackendCommand::PopFillRule,
+        draw,
+    )
+}
+
+/// A polygon made of several contours filled as one shape, e.g. a donut slice with its
+/// inner arc as a second contour. Which parts are filled depends on the current [`FillRule`].
+///
+/// ```ignore
+/// chart.draw_series(std::iter::once(MultiPolygon::new(
+///     vec![outer_ring, inner_ring],
+///     BLUE.filled(),
+/// )))?;
+/// ```
+#[cfg(feature = "plotters")]
+pub struct MultiPolygon<Coord> {
+    contours: Vec<Vec<Coord>>,
+    style: ShapeStyle,
+}
+
+#[cfg(feature = "plotters")]
+impl<Coord> MultiPolygon<Coord> {
+    pub fn new(contours: Vec<Vec<Coord>>, style: impl Into<ShapeStyle>) -> Self {
+        Self {
+            contours,
+            style: style.into(),
+        }
+    }
+}
+
+#[cfg(feature = "plotters")]
+impl<'a, Coord> PointCollection<'a, Coord> for &'a MultiPolygon<Coord> {
+    type Point = &'a Coord;
+    type IntoIter = Vec<&'a Coord>;
+
+    fn point_iter(self) -> Self::IntoIter {
+        self.contours.iter().flatten().collect()
+    }
+}
+
+#[cfg(feature = "plotters")]
+impl<Coord, DB: BackendState> Drawable<DB> for MultiPolygon<Coord> {
+    fn draw<I: Iterator<Item = BackendCoord>>(
+        &self,
+        mut pos: I,
+        backend: &mut DB,
+        _: (u32, u32),
+    ) -> Result<(), DrawingErrorKind<crate::Error>> {
+        // the points arrive flattened, split them again along the original contours
+        let contours: Vec<Vec<BackendCoord>> = self
+            .contours
+            .iter()
+            .map(|contour| pos.by_ref().take(contour.len()).collect())
+            .collect();
+        backend.fill_contours(contours, &self.style)
+    }
+}
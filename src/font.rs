@@ -0,0 +1,95 @@
+use gpui::{App, Font, FontStyle, FontWeight, Global, SharedString};
+use plotters_backend::{FontFamily, FontStyle as PlottersFontStyle};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Maps the font family names used by plotters, like `"sans-serif"`, to fonts installed on the system.
+///
+/// The backend reads the aliases from the gpui global, so they can be configured once for all charts:
+///
+/// ```ignore
+/// cx.set_global(FontAliases::default().alias("sans-serif", "Inter"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct FontAliases {
+    aliases: HashMap<SharedString, SharedString>,
+}
+
+impl Global for FontAliases {}
+
+impl Default for FontAliases {
+    fn default() -> Self {
+        let (serif, sans_serif, monospace) = if cfg!(target_os = "macos") {
+            ("Times New Roman", "Helvetica", "Menlo")
+        } else if cfg!(target_os = "windows") {
+            ("Times New Roman", "Segoe UI", "Consolas")
+        } else {
+            ("DejaVu Serif", "DejaVu Sans", "DejaVu Sans Mono")
+        };
+
+        Self::new()
+            .alias(FontFamily::Serif.as_str(), serif)
+            .alias(FontFamily::SansSerif.as_str(), sans_serif)
+            .alias(FontFamily::Monospace.as_str(), monospace)
+    }
+}
+
+impl FontAliases {
+    /// The aliases set as gpui global, or the default ones, which are only built once
+    pub fn current(cx: &App) -> &Self {
+        static DEFAULT: OnceLock<FontAliases> = OnceLock::new();
+        cx.try_global::<Self>()
+            .unwrap_or_else(|| DEFAULT.get_or_init(Self::default))
+    }
+
+    /// Create an empty alias table, every family is looked up by its own name
+    pub fn new() -> Self {
+        Self {
+            aliases: HashMap::new(),
+        }
+    }
+
+    /// Resolve `name` to the concrete font `family`
+    pub fn alias(mut self, name: impl Into<SharedString>, family: impl Into<SharedString>) -> Self {
+        self.aliases.insert(name.into(), family.into());
+        self
+    }
+
+    /// The concrete family name for a plotters font family
+    pub fn resolve(&self, family: FontFamily) -> SharedString {
+        let name = family.as_str();
+        match self.aliases.get(name) {
+            Some(family) => family.clone(),
+            None => SharedString::from(name.to_string()),
+        }
+    }
+
+    /// Build the gpui font for a plotters family and style, keeping features and fallbacks of `base`
+    pub fn font(&self, family: FontFamily, style: PlottersFontStyle, base: Font) -> Font {
+        let (weight, style) = match style {
+            PlottersFontStyle::Normal => (FontWeight::NORMAL, FontStyle::Normal),
+            PlottersFontStyle::Oblique => (FontWeight::NORMAL, FontStyle::Oblique),
+            PlottersFontStyle::Italic => (FontWeight::NORMAL, FontStyle::Italic),
+            PlottersFontStyle::Bold => (FontWeight::BOLD, FontStyle::Normal),
+        };
+
+        Font {
+            family: self.resolve(family),
+            weight,
+            style,
+            ..base
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_uses_aliases() {
+        let aliases = FontAliases::new().alias("sans-serif", "Inter");
+        assert_eq!(aliases.resolve(FontFamily::SansSerif), "Inter");
+        assert_eq!(aliases.resolve(FontFamily::Name("Fira Code")), "Fira Code");
+    }
+}
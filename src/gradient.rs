@@ -0,0 +1,259 @@
+#[cfg(feature = "plotters")]
+use crate::backend::BackendState;
+#[cfg(feature = "plotters")]
+use crate::command::{with_command, BackendCommand};
+use crate::record::Color;
+#[cfg(feature = "plotters")]
+use plotters::coord::CoordTranslate;
+#[cfg(feature = "plotters")]
+use plotters::drawing::{DrawingArea, DrawingAreaErrorKind};
+#[cfg(feature = "plotters")]
+use plotters::element::{Drawable, PointCollection};
+#[cfg(feature = "plotters")]
+use plotters_backend::{BackendCoord, DrawingErrorKind};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "plotters")]
+use std::borrow::Borrow;
+
+/// The direction a [`LinearGradient`] runs in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GradientAxis {
+    /// From the left edge of a shape to its right edge
+    Horizontal,
+    /// From the top edge of a shape to its bottom edge
+    #[default]
+    Vertical,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GradientStop {
+    /// Position along the axis, 0 at the start of the shape and 1 at its end
+    pub offset: f32,
+    pub color: Color,
+}
+
+/// A gradient filling shapes along an axis of their bounding box, so every bar of a series
+/// gets the full gradient whatever its height. Before the first and after the last stop the
+/// color of that stop is used.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LinearGradient {
+    pub axis: GradientAxis,
+    /// Stops ordered by offset
+    pub stops: Vec<GradientStop>,
+}
+
+impl LinearGradient {
+    pub fn new(axis: GradientAxis) -> Self {
+        Self {
+            axis,
+            stops: vec![],
+        }
+    }
+
+    pub fn horizontal() -> Self {
+        Self::new(GradientAxis::Horizontal)
+    }
+
+    pub fn vertical() -> Self {
+        Self::new(GradientAxis::Vertical)
+    }
+
+    /// Add a stop, keeping the stops ordered by offset
+    pub fn stop(mut self, offset: f32, color: impl Into<Color>) -> Self {
+        let stop = GradientStop {
+            offset,
+            color: color.into(),
+        };
+        let index = self.stops.partition_point(|s| s.offset <= offset);
+        self.stops.insert(index, stop);
+        self
+    }
+
+    /// The color at `t` along the axis, interpolated between the surrounding stops
+    pub fn color_at(&self, t: f32) -> Option<Color> {
+        let first = self.stops.first()?;
+        let last = self.stops.last()?;
+        if t <= first.offset {
+            return Some(first.color);
+        }
+        if t >= last.offset {
+            return Some(last.color);
+        }
+        let index = self.stops.partition_point(|s| s.offset <= t);
+        let (a, b) = (&self.stops[index - 1], &self.stops[index]);
+        let f = ((t - a.offset) / (b.offset - a.offset)) as f64;
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * f).round() as u8;
+        Some(Color {
+            rgb: (
+                mix(a.color.rgb.0, b.color.rgb.0),
+                mix(a.color.rgb.1, b.color.rgb.1),
+                mix(a.color.rgb.2, b.color.rgb.2),
+            ),
+            alpha: a.color.alpha + (b.color.alpha - a.color.alpha) * f,
+        })
+    }
+
+    /// Wrap `element` so it is filled with this gradient, see [`GradientFill`]
+    #[cfg(feature = "plotters")]
+    pub fn fill<Coord: Clone, E>(&self, element: E) -> GradientFill<Coord, E>
+    where
+        for<'a> &'a E: PointCollection<'a, Coord>,
+    {
+        GradientFill {
+            points: element
+                .point_iter()
+                .into_iter()
+                .map(|p| p.borrow().clone())
+                .collect(),
+            element,
+            gradient: self.clone(),
+        }
+    }
+}
+
+/// Fill the rectangles and polygons `draw` fills with `gradient` instead of their flat
+/// color, e.g. an area chart fading out towards the axis:
+///
+/// ```ignore
+/// let fade = LinearGradient::vertical()
+///     .stop(0.0, BLUE.mix(0.6).to_backend_color())
+///     .stop(1.0, BLUE.mix(0.0).to_backend_color());
+/// with_gradient(root, fade, || {
+///     chart.draw_series(AreaSeries::new(data, 0.0, BLUE.mix(0.6)).border_style(BLUE))
+/// })??;
+/// ```
+#[cfg(feature = "plotters")]
+pub fn with_gradient<DB: BackendState, CT: CoordTranslate, R>(
+    area: &DrawingArea<DB, CT>,
+    gradient: LinearGradient,
+    draw: impl FnOnce() -> R,
+) -> Result<R, DrawingAreaErrorKind<crate::Error>> {
+    with_command(
+        area,
+        BackendCommand::PushGradient(gradient),
+        BackendCommand::PopGradient,
+        draw,
+    )
+}
+
+/// An element filled with a gradient instead of the color of its style, so series can be
+/// drawn with one without [`with_gradient`] around them:
+///
+/// ```ignore
+/// chart.draw_series(
+///     data.iter()
+///         .map(|&(x, y)| fade.fill(Rectangle::new([(x, 0.0), (x + 1, y)], BLUE.filled()))),
+/// )?;
+/// ```
+#[cfg(feature = "plotters")]
+pub struct GradientFill<Coord, E> {
+    /// The points of `element`, copied like `DynElement` does since forwarding the point
+    /// collection of any inner element overflows trait resolution
+    points: Vec<Coord>,
+    element: E,
+    gradient: LinearGradient,
+}
+
+#[cfg(feature = "plotters")]
+impl<'a, Coord, E> PointCollection<'a, Coord> for &'a GradientFill<Coord, E> {
+    type Point = &'a Coord;
+    type IntoIter = &'a Vec<Coord>;
+
+    fn point_iter(self) -> Self::IntoIter {
+        &self.points
+    }
+}
+
+#[cfg(feature = "plotters")]
+impl<Coord, E: Drawable<DB>, DB: BackendState> Drawable<DB> for GradientFill<Coord, E> {
+    fn draw<I: Iterator<Item = BackendCoord>>(
+        &self,
+        pos: I,
+        backend: &mut DB,
+        parent_dim: (u32, u32),
+    ) -> Result<(), DrawingErrorKind<crate::Error>> {
+        backend.push_gradient(self.gradient.clone())?;
+        let result = self.element.draw(pos, backend, parent_dim);
+        backend.pop_gradient()?;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color(rgb: (u8, u8, u8), alpha: f64) -> Color {
+        Color { rgb, alpha }
+    }
+
+    fn fade() -> LinearGradient {
+        LinearGradient::vertical()
+            .stop(0.2, color((0, 0, 0), 1.0))
+            .stop(0.6, color((200, 100, 50), 0.0))
+    }
+
+    #[test]
+    fn before_the_first_stop() {
+        assert_eq!(fade().color_at(0.0), Some(color((0, 0, 0), 1.0)));
+        assert_eq!(fade().color_at(0.2), Some(color((0, 0, 0), 1.0)));
+    }
+
+    #[test]
+    fn after_the_last_stop() {
+        assert_eq!(fade().color_at(0.6), Some(color((200, 100, 50), 0.0)));
+        assert_eq!(fade().color_at(1.0), Some(color((200, 100, 50), 0.0)));
+    }
+
+    #[test]
+    fn between_stops() {
+        assert_eq!(fade().color_at(0.3), Some(color((50, 25, 13), 0.75)));
+    }
+
+    #[test]
+    fn unsorted_stops() {
+        let gradient = LinearGradient::horizontal()
+            .stop(1.0, color((0, 0, 255), 1.0))
+            .stop(0.0, color((255, 0, 0), 1.0))
+            .stop(0.5, color((0, 255, 0), 1.0));
+        let offsets: Vec<_> = gradient.stops.iter().map(|s| s.offset).collect();
+        assert_eq!(offsets, vec![0.0, 0.5, 1.0]);
+        assert_eq!(gradient.color_at(0.25), Some(color((128, 128, 0), 1.0)));
+        assert_eq!(gradient.color_at(0.75), Some(color((0, 128, 128), 1.0)));
+    }
+
+    #[test]
+    fn single_stop() {
+        let gradient = LinearGradient::vertical().stop(0.5, color((1, 2, 3), 0.5));
+        for t in [0.0, 0.5, 1.0] {
+            assert_eq!(gradient.color_at(t), Some(color((1, 2, 3), 0.5)));
+        }
+        assert_eq!(LinearGradient::vertical().color_at(0.5), None);
+    }
+
+    #[cfg(feature = "plotters")]
+    #[test]
+    fn fill_wraps_the_element() {
+        use crate::record::{DisplayList, DrawCommand, RecordingBackend};
+        use plotters::prelude::*;
+
+        let mut list = DisplayList::default();
+        {
+            let root = RecordingBackend::new(&mut list, (10, 10)).into_drawing_area();
+            root.draw(&fade().fill(Rectangle::new([(1, 1), (5, 5)], BLUE.filled())))
+                .unwrap();
+        }
+        assert!(matches!(
+            list.commands.as_slice(),
+            [
+                DrawCommand::PushGradient(gradient),
+                DrawCommand::Rect { fill: true, .. },
+                DrawCommand::PopGradient,
+            ] if *gradient == fade()
+        ));
+    }
+}
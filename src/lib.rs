@@ -1,8 +1,30 @@
 pub mod backend;
+mod bitmap;
+#[cfg(feature = "plotters")]
+pub mod clip;
+#[cfg(feature = "plotters")]
+mod command;
+#[cfg(feature = "plotters")]
+pub mod coords;
 #[cfg(feature = "plotters")]
 pub mod element;
+mod error;
+pub mod fill;
+pub mod font;
+pub mod gradient;
 pub mod line;
+mod raster;
+pub mod record;
+#[cfg(feature = "plotters")]
+pub mod snapshot;
+pub mod software;
+#[cfg(feature = "plotters")]
+pub mod stroke;
+pub mod svg;
+#[cfg(feature = "plotters")]
+pub mod tooltip;
 mod utils;
+pub mod view;
 
-pub type Error = std::io::Error;
+pub use error::Error;
 pub type DrawingErrorKind = plotters_backend::DrawingErrorKind<Error>;
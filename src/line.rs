@@ -1,11 +1,83 @@
-use gpui::{px, Hsla, PathBuilder, Pixels, Point, Window};
+use gpui::{px, Hsla, PathBuilder, PathStyle, Pixels, Point, StrokeOptions, Window};
+use lyon::tessellation::{LineCap as LyonLineCap, LineJoin as LyonLineJoin};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use tracing::warn;
 
+/// How the open ends of a line, and of every dash, are drawn
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LineCap {
+    /// The stroke ends exactly at the end point
+    #[default]
+    Butt,
+    /// A half circle is added past the end point
+    Round,
+    /// A half square is added past the end point
+    Square,
+}
+
+/// How two segments of a line are connected
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LineJoin {
+    /// Extend the outer edges until they meet
+    #[default]
+    Miter,
+    /// Round the outer corner
+    Round,
+    /// Cut the outer corner
+    Bevel,
+}
+
+/// Cap, join and dash pattern of the strokes drawn by
+/// [`GpuiBackend`](crate::backend::GpuiBackend), dash lengths are in backend pixels
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StrokeStyle {
+    pub cap: LineCap,
+    pub join: LineJoin,
+    /// Alternating lengths of dashes and gaps, empty for a solid line
+    pub dash: Vec<f32>,
+    /// Distance into the dash pattern at which the line starts
+    pub dash_offset: f32,
+}
+
+impl StrokeStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn dash(mut self, dash: impl Into<Vec<f32>>, offset: f32) -> Self {
+        self.dash = dash.into();
+        self.dash_offset = offset;
+        self
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Line {
     pub points: Vec<Point<Pixels>>,
     pub width: Pixels,
     pub color: Hsla,
+    /// Connect the last point back to the first one
+    pub closed: bool,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    /// Alternating lengths of dashes and gaps, empty for a solid line
+    pub dash: Vec<Pixels>,
+    /// Distance into the dash pattern at which the line starts
+    pub dash_offset: Pixels,
 }
 
 impl Default for Line {
@@ -20,6 +92,11 @@ impl Line {
             points: vec![],
             width: 1.0.into(),
             color: gpui::black(),
+            closed: false,
+            cap: LineCap::default(),
+            join: LineJoin::default(),
+            dash: vec![],
+            dash_offset: px(0.0),
         }
     }
 
@@ -40,6 +117,27 @@ impl Line {
         self
     }
 
+    pub fn closed(mut self, closed: bool) -> Self {
+        self.closed = closed;
+        self
+    }
+
+    pub fn cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn dash(mut self, dash: impl Into<Vec<Pixels>>, offset: impl Into<Pixels>) -> Self {
+        self.dash = dash.into();
+        self.dash_offset = offset.into();
+        self
+    }
+
     pub fn add_point(&mut self, point: Point<Pixels>) {
         self.points.push(point);
     }
@@ -54,17 +152,190 @@ impl Line {
         }
 
         let mut builder = PathBuilder::stroke(px(self.width.0));
-        let Some(first_p) = self.points.first() else {
-            return;
+        builder.style = PathStyle::Stroke(self.stroke_options());
+        self.append_to(&mut builder);
+
+        if let Ok(path) = builder.build() {
+            window.paint_path(path, self.color);
+        }
+    }
+
+    /// Add the contours of this line to a stroke path, so several lines sharing width, color
+    /// and stroke options can be painted as one path
+    pub(crate) fn append_to(&self, builder: &mut PathBuilder) {
+        for contour in self.contours() {
+            let Some(first_p) = contour.first() else {
+                continue;
+            };
+            builder.move_to(*first_p);
+            for p in contour.iter().skip(1) {
+                builder.line_to(*p);
+            }
+            if self.closed && self.dash.is_empty() {
+                builder.close();
+            }
+        }
+    }
+
+    pub(crate) fn stroke_options(&self) -> StrokeOptions {
+        let cap = match self.cap {
+            LineCap::Butt => LyonLineCap::Butt,
+            LineCap::Round => LyonLineCap::Round,
+            LineCap::Square => LyonLineCap::Square,
         };
+        let join = match self.join {
+            LineJoin::Miter => LyonLineJoin::Miter,
+            LineJoin::Round => LyonLineJoin::Round,
+            LineJoin::Bevel => LyonLineJoin::Bevel,
+        };
+        StrokeOptions::default()
+            .with_line_width(self.width.0)
+            .with_line_cap(cap)
+            .with_line_join(join)
+    }
 
-        builder.move_to(*first_p);
-        for p in self.points.iter().skip(1) {
-            builder.line_to(*p);
+    /// The polylines to stroke, one per dash, or the whole line when it is solid
+    pub(crate) fn contours(&self) -> Vec<Vec<Point<Pixels>>> {
+        // an odd pattern is repeated to get an even one, like SVG does
+        let mut pattern: Vec<f32> = self.dash.iter().map(|d| d.0).collect();
+        if pattern.len() % 2 == 1 {
+            pattern.extend_from_within(..);
+        }
+        let total: f32 = pattern.iter().sum();
+        if pattern.is_empty() || pattern.iter().any(|d| *d < 0.0) || total <= 0.0 {
+            return vec![self.points.clone()];
         }
 
-        if let Ok(path) = builder.build() {
-            window.paint_path(path, self.color);
+        let mut segments: Vec<(Point<Pixels>, Point<Pixels>)> =
+            self.points.windows(2).map(|w| (w[0], w[1])).collect();
+        if self.closed && self.points.len() > 2 {
+            segments.push((self.points[self.points.len() - 1], self.points[0]));
+        }
+
+        // find where in the pattern the line starts, a zero length dash at the very start is
+        // still drawn, e.g. the first dot of a dotted line
+        let mut index = 0;
+        let mut remaining = self.dash_offset.0.rem_euclid(total);
+        while remaining > pattern[index] || (remaining == pattern[index] && remaining > 0.0) {
+            remaining -= pattern[index];
+            index = (index + 1) % pattern.len();
+        }
+        remaining = pattern[index] - remaining;
+
+        let mut contours = vec![];
+        let mut current = vec![];
+        if let Some((start, _)) = segments.first() {
+            if index % 2 == 0 {
+                current.push(*start);
+            }
+        }
+        for (start, end) in segments {
+            let delta = end - start;
+            let length = (delta.x.0 * delta.x.0 + delta.y.0 * delta.y.0).sqrt();
+            let mut travelled = 0.0;
+            while length - travelled > remaining {
+                travelled += remaining;
+                let t = travelled / length;
+                current.push(start + delta * t);
+                if index % 2 == 0 {
+                    contours.push(std::mem::take(&mut current));
+                }
+                index = (index + 1) % pattern.len();
+                remaining = pattern[index];
+            }
+            remaining -= length - travelled;
+            if index % 2 == 0 {
+                current.push(end);
+            }
+        }
+        if index % 2 == 0 && current.len() > 1 {
+            contours.push(current);
+        }
+
+        contours
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::point;
+
+    fn line(points: &[(f32, f32)]) -> Line {
+        let mut line = Line::new();
+        for (x, y) in points {
+            line.add_point(point(px(*x), px(*y)));
+        }
+        line
+    }
+
+    fn coords(contours: Vec<Vec<Point<Pixels>>>) -> Vec<Vec<(f32, f32)>> {
+        contours
+            .into_iter()
+            .map(|c| c.into_iter().map(|p| (p.x.0, p.y.0)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn solid_line_is_one_contour() {
+        let line = line(&[(0.0, 0.0), (10.0, 0.0), (10.0, 5.0)]);
+        assert_eq!(
+            coords(line.contours()),
+            vec![vec![(0.0, 0.0), (10.0, 0.0), (10.0, 5.0)]]
+        );
+    }
+
+    #[test]
+    fn odd_pattern_is_repeated() {
+        let line = line(&[(0.0, 0.0), (10.0, 0.0)]).dash([px(2.0)], px(0.0));
+        assert_eq!(
+            coords(line.contours()),
+            vec![
+                vec![(0.0, 0.0), (2.0, 0.0)],
+                vec![(4.0, 0.0), (6.0, 0.0)],
+                vec![(8.0, 0.0), (10.0, 0.0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn offset_wraps_around_the_pattern() {
+        let expected = vec![
+            vec![(0.0, 0.0), (1.0, 0.0)],
+            vec![(3.0, 0.0), (5.0, 0.0)],
+            vec![(7.0, 0.0), (9.0, 0.0)],
+        ];
+        for offset in [1.0, 5.0, -3.0] {
+            let line = line(&[(0.0, 0.0), (10.0, 0.0)]).dash([px(2.0), px(2.0)], px(offset));
+            assert_eq!(coords(line.contours()), expected, "offset {}", offset);
         }
     }
+
+    #[test]
+    fn closed_line_dashes_the_closing_segment() {
+        let square = [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)];
+        let line = line(&square).closed(true).dash([px(5.0), px(5.0)], px(0.0));
+        assert_eq!(
+            coords(line.contours()),
+            vec![
+                vec![(0.0, 0.0), (5.0, 0.0)],
+                vec![(10.0, 0.0), (10.0, 5.0)],
+                vec![(10.0, 10.0), (5.0, 10.0)],
+                vec![(0.0, 10.0), (0.0, 5.0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn dotted_line_starts_with_a_dot() {
+        let line = line(&[(0.0, 0.0), (10.0, 0.0)]).dash([px(0.0), px(4.0)], px(0.0));
+        assert_eq!(
+            coords(line.contours()),
+            vec![
+                vec![(0.0, 0.0), (0.0, 0.0)],
+                vec![(4.0, 0.0), (4.0, 0.0)],
+                vec![(8.0, 0.0), (8.0, 0.0)],
+            ]
+        );
+    }
 }
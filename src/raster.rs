@@ -0,0 +1,140 @@
+use image::codecs::png::PngEncoder;
+use image::{ExtendedColorType, ImageEncoder};
+use plotters_backend::{BackendColor, BackendCoord};
+
+/// CPU side BGRA buffer that collects `draw_pixel` calls so they can be painted as one image.
+///
+/// Colors are stored with straight alpha, new pixels are blended over the existing content.
+/// Only the rectangle around the pixels drawn since the last [`Self::take`] is taken out, so
+/// a small batch doesn't cost an image of the whole raster.
+pub struct PixelRaster {
+    width: u32,
+    height: u32,
+    /// allocated on the first pixel and kept for the next batches
+    data: Vec<u8>,
+    /// `(x0, y0, x1, y1)` around the pixels drawn so far, exclusive at the bottom right
+    dirty: Option<(i32, i32, i32, i32)>,
+}
+
+/// The part of a [`PixelRaster`] that was drawn to
+pub struct RasterPatch {
+    /// Top left corner in the raster
+    pub origin: BackendCoord,
+    pub size: (u32, u32),
+    pub bgra: Vec<u8>,
+}
+
+impl PixelRaster {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![],
+            dirty: None,
+        }
+    }
+
+    /// Blend `color` over the pixel at `point`, pixels outside the raster are dropped
+    pub fn blend(&mut self, point: BackendCoord, color: BackendColor) {
+        let (x, y) = point;
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return;
+        }
+        let alpha = color.alpha.clamp(0.0, 1.0) as f32;
+        if alpha <= 0.0 {
+            return;
+        }
+        if self.data.is_empty() {
+            self.data = vec![0; self.width as usize * self.height as usize * 4];
+        }
+
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        let pixel = &mut self.data[index..index + 4];
+        let src = [color.rgb.2, color.rgb.1, color.rgb.0];
+        let dst_alpha = pixel[3] as f32 / 255.0;
+        let out_alpha = alpha + dst_alpha * (1.0 - alpha);
+        for (dst, src) in pixel.iter_mut().zip(src) {
+            let blended =
+                (src as f32 * alpha + *dst as f32 * dst_alpha * (1.0 - alpha)) / out_alpha;
+            *dst = blended.round() as u8;
+        }
+        pixel[3] = (out_alpha * 255.0).round() as u8;
+        self.dirty = Some(match self.dirty {
+            Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x + 1), y1.max(y + 1)),
+            None => (x, y, x + 1, y + 1),
+        });
+    }
+
+    /// Take the pixels drawn since the last call out of the raster, leaving it empty
+    pub fn take(&mut self) -> Option<RasterPatch> {
+        let (x0, y0, x1, y1) = self.dirty.take()?;
+        let (width, height) = ((x1 - x0) as u32, (y1 - y0) as u32);
+        let stride = self.width as usize * 4;
+        let row_len = width as usize * 4;
+        let mut bgra = Vec::with_capacity(row_len * height as usize);
+        for y in y0..y1 {
+            let start = y as usize * stride + x0 as usize * 4;
+            let row = &mut self.data[start..start + row_len];
+            bgra.extend_from_slice(row);
+            row.fill(0);
+        }
+        Some(RasterPatch {
+            origin: (x0, y0),
+            size: (width, height),
+            bgra,
+        })
+    }
+}
+
+/// Encode an RGBA buffer as PNG, `None` if the buffer doesn't match the size
+pub fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Option<Vec<u8>> {
+    let mut png = vec![];
+    PngEncoder::new(&mut png)
+        .write_image(rgba, width, height, ExtendedColorType::Rgba8)
+        .ok()?;
+    Some(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: BackendColor = BackendColor {
+        alpha: 1.0,
+        rgb: (255, 0, 0),
+    };
+
+    #[test]
+    fn take_returns_the_drawn_rectangle() {
+        let mut raster = PixelRaster::new(100, 100);
+        raster.blend((10, 20), RED);
+        raster.blend((12, 21), RED);
+        let patch = raster.take().unwrap();
+        assert_eq!(patch.origin, (10, 20));
+        assert_eq!(patch.size, (3, 2));
+        assert_eq!(&patch.bgra[..4], &[0, 0, 255, 255]);
+        assert_eq!(&patch.bgra[4..8], &[0; 4]);
+        assert_eq!(&patch.bgra[20..], &[0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn take_clears_the_raster() {
+        let mut raster = PixelRaster::new(10, 10);
+        assert!(raster.take().is_none());
+        raster.blend((5, 5), RED);
+        raster.take().unwrap();
+        assert!(raster.take().is_none());
+        raster.blend((0, 0), RED);
+        let patch = raster.take().unwrap();
+        assert_eq!((patch.origin, patch.size), ((0, 0), (1, 1)));
+        assert!(raster.data.iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn pixels_outside_are_dropped() {
+        let mut raster = PixelRaster::new(10, 10);
+        raster.blend((-1, 0), RED);
+        raster.blend((0, 10), RED);
+        assert!(raster.take().is_none());
+    }
+}
@@ -0,0 +1,659 @@
+use crate::backend::BackendState;
+use crate::error::Error;
+use crate::fill::FillRule;
+use crate::gradient::LinearGradient;
+use crate::line::StrokeStyle;
+use crate::utils::text_lines;
+#[cfg(feature = "plotters")]
+use plotters::style::{FontDesc, FontError};
+use plotters_backend::{
+    text_anchor::{HPos, Pos, VPos},
+    BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingBackend, DrawingErrorKind,
+    FontFamily, FontStyle, FontTransform,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use std::cell::RefCell;
+
+/// Distance of the baseline from the top of a layout box of the font backend of plotters, as
+/// a share of its height. Its ttf rasterizer (`FontDataInternal::draw` in
+/// `plotters/src/style/font/ttf.rs`) lays glyphs out on an em of `size / 1.24` pixels, the
+/// height of the layout box, puts their baseline 1 em below the top of the glyph canvas and
+/// moves the canvas up by 0.24 em. `layout_box` itself only reports the advance and the em.
+const PLOTTERS_BASELINE: f32 = 0.76;
+
+/// An RGB color with alpha, as handed to the backend by plotters
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Color {
+    pub rgb: (u8, u8, u8),
+    pub alpha: f64,
+}
+
+impl From<BackendColor> for Color {
+    fn from(color: BackendColor) -> Self {
+        Self {
+            rgb: color.rgb,
+            alpha: color.alpha,
+        }
+    }
+}
+
+impl From<Color> for BackendColor {
+    fn from(color: Color) -> Self {
+        BackendColor {
+            rgb: color.rgb,
+            alpha: color.alpha,
+        }
+    }
+}
+
+/// Color and width of a recorded shape
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Stroke {
+    pub color: Color,
+    pub width: u32,
+}
+
+impl Stroke {
+    pub(crate) fn of<S: BackendStyle>(style: &S) -> Self {
+        Self {
+            color: style.color().into(),
+            width: style.stroke_width(),
+        }
+    }
+}
+
+impl BackendStyle for Stroke {
+    fn color(&self) -> BackendColor {
+        self.color.into()
+    }
+
+    fn stroke_width(&self) -> u32 {
+        self.width
+    }
+}
+
+/// Horizontal position of the anchor point of a text
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum HAlign {
+    Left,
+    Center,
+    Right,
+}
+
+/// Vertical position of the anchor point of a text
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VAlign {
+    Top,
+    Center,
+    Bottom,
+}
+
+/// A self contained copy of the text style plotters passed to the backend
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TextStyle {
+    /// Font family name, like `"sans-serif"`
+    pub family: String,
+    /// The installed font the family resolved to when the text was laid out, like
+    /// `"DejaVu Sans"`, `None` when it was recorded without gpui
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub font: Option<String>,
+    /// `"normal"`, `"bold"`, `"italic"` or `"oblique"`
+    pub style: String,
+    /// Font size in backend pixels
+    pub size: f64,
+    /// Clockwise rotation in degrees, one of 0, 90, 180 and 270
+    pub rotation: u16,
+    pub h_align: HAlign,
+    pub v_align: VAlign,
+    pub color: Color,
+}
+
+impl TextStyle {
+    pub(crate) fn of<S: BackendTextStyle>(style: &S) -> Self {
+        let anchor = style.anchor();
+        Self {
+            family: style.family().as_str().to_string(),
+            font: None,
+            style: style.style().as_str().to_string(),
+            size: style.size(),
+            rotation: match style.transform() {
+                FontTransform::None => 0,
+                FontTransform::Rotate90 => 90,
+                FontTransform::Rotate180 => 180,
+                FontTransform::Rotate270 => 270,
+            },
+            h_align: match anchor.h_pos {
+                HPos::Left => HAlign::Left,
+                HPos::Center => HAlign::Center,
+                HPos::Right => HAlign::Right,
+            },
+            v_align: match anchor.v_pos {
+                VPos::Top => VAlign::Top,
+                VPos::Center => VAlign::Center,
+                VPos::Bottom => VAlign::Bottom,
+            },
+            color: style.color().into(),
+        }
+    }
+
+    /// The extent of `text` as the font backend of plotters lays it out, which draws rotated
+    /// text on every backend and all text in [`software`](crate::software). Needs the
+    /// `plotters` feature.
+    pub(crate) fn layout_metrics(&self, text: &str) -> Result<TextMetrics, Error> {
+        let layouts = text_lines(text)
+            .map(|line| self.layout_box(line))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| Error::FontLookup(err.to_string()))?;
+        let width = layouts
+            .iter()
+            .map(|((min_x, _), (max_x, _))| max_x - min_x)
+            .max()
+            .unwrap_or(0) as f32;
+        let line_height = layouts
+            .iter()
+            .map(|((_, min_y), (_, max_y))| max_y - min_y)
+            .max()
+            .unwrap_or(0) as f32;
+        let height = line_height * layouts.len() as f32;
+        let ascent = line_height * PLOTTERS_BASELINE;
+        Ok(TextMetrics {
+            width,
+            ascent,
+            descent: height - ascent,
+        })
+    }
+
+    /// The plotters text style, used to rasterize rotated text
+    #[cfg(feature = "plotters")]
+    fn text_style(&self) -> plotters::style::TextStyle<'_> {
+        plotters::style::TextStyle {
+            font: FontDesc::new(self.family(), self.size, self.style()).transform(self.transform()),
+            color: self.color.into(),
+            pos: self.anchor(),
+        }
+    }
+}
+
+impl BackendTextStyle for TextStyle {
+    #[cfg(feature = "plotters")]
+    type FontError = FontError;
+    #[cfg(not(feature = "plotters"))]
+    type FontError = Error;
+
+    fn color(&self) -> BackendColor {
+        self.color.into()
+    }
+
+    fn size(&self) -> f64 {
+        self.size
+    }
+
+    fn transform(&self) -> FontTransform {
+        match self.rotation {
+            90 => FontTransform::Rotate90,
+            180 => FontTransform::Rotate180,
+            270 => FontTransform::Rotate270,
+            _ => FontTransform::None,
+        }
+    }
+
+    fn style(&self) -> FontStyle {
+        FontStyle::from(self.style.as_str())
+    }
+
+    fn anchor(&self) -> Pos {
+        let h_pos = match self.h_align {
+            HAlign::Left => HPos::Left,
+            HAlign::Center => HPos::Center,
+            HAlign::Right => HPos::Right,
+        };
+        let v_pos = match self.v_align {
+            VAlign::Top => VPos::Top,
+            VAlign::Center => VPos::Center,
+            VAlign::Bottom => VPos::Bottom,
+        };
+        Pos::new(h_pos, v_pos)
+    }
+
+    fn family(&self) -> FontFamily<'_> {
+        FontFamily::from(self.family.as_str())
+    }
+
+    fn layout_box(&self, text: &str) -> Result<((i32, i32), (i32, i32)), Self::FontError> {
+        #[cfg(feature = "plotters")]
+        return self.text_style().layout_box(text);
+        #[cfg(not(feature = "plotters"))]
+        Err(Error::FontLookup(format!(
+            "laying out {:?} needs the plotters feature",
+            text
+        )))
+    }
+
+    fn draw<E, DrawFunc: FnMut(i32, i32, BackendColor) -> Result<(), E>>(
+        &self,
+        text: &str,
+        pos: BackendCoord,
+        draw: DrawFunc,
+    ) -> Result<Result<(), E>, Self::FontError> {
+        #[cfg(feature = "plotters")]
+        return self.text_style().draw(text, pos, draw);
+        #[cfg(not(feature = "plotters"))]
+        {
+            let _ = (pos, draw);
+            Err(Error::FontLookup(format!(
+                "rasterizing {:?} needs the plotters feature",
+                text
+            )))
+        }
+    }
+}
+
+/// Extent of a line of text in backend pixels, its top is `ascent` above the baseline
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TextMetrics {
+    pub width: f32,
+    pub ascent: f32,
+    pub descent: f32,
+}
+
+impl TextMetrics {
+    pub fn height(&self) -> f32 {
+        self.ascent + self.descent
+    }
+}
+
+/// A primitive recorded by [`RecordingBackend`], coordinates and sizes are in backend pixels
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DrawCommand {
+    Pixel {
+        point: BackendCoord,
+        color: Color,
+    },
+    Line {
+        from: BackendCoord,
+        to: BackendCoord,
+        stroke: Stroke,
+    },
+    Rect {
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        stroke: Stroke,
+        fill: bool,
+    },
+    Path {
+        points: Vec<BackendCoord>,
+        stroke: Stroke,
+    },
+    Circle {
+        center: BackendCoord,
+        radius: u32,
+        stroke: Stroke,
+        fill: bool,
+    },
+    Polygon {
+        points: Vec<BackendCoord>,
+        color: Color,
+    },
+    /// Several contours filled as one shape
+    MultiPolygon {
+        contours: Vec<Vec<BackendCoord>>,
+        color: Color,
+    },
+    Text {
+        text: String,
+        pos: BackendCoord,
+        style: TextStyle,
+        /// The extent the text was laid out with, `None` when it was recorded without a
+        /// text measurer
+        metrics: Option<TextMetrics>,
+    },
+    /// An RGB bitmap, 3 bytes per pixel
+    Bitmap {
+        pos: BackendCoord,
+        width: u32,
+        height: u32,
+        rgb: Vec<u8>,
+    },
+    PushClip {
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+    },
+    PopClip,
+    PushStrokeStyle(StrokeStyle),
+    PopStrokeStyle,
+    PushFillRule(FillRule),
+    PopFillRule,
+    PushGradient(LinearGradient),
+    PopGradient,
+}
+
+/// Everything a chart drew, in drawing order
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DisplayList {
+    /// Size of the recorded drawing area in backend pixels
+    pub size: (u32, u32),
+    pub commands: Vec<DrawCommand>,
+}
+
+impl DisplayList {
+    pub fn new(size: (u32, u32)) -> Self {
+        Self {
+            size,
+            commands: vec![],
+        }
+    }
+
+    /// Draw the recorded primitives on `backend`, e.g. a [`GpuiBackend`](crate::backend::GpuiBackend)
+    /// to repaint a chart without running it again
+    pub fn replay<DB: BackendState>(
+        &self,
+        backend: &mut DB,
+    ) -> Result<(), DrawingErrorKind<Error>> {
+        for command in &self.commands {
+            match command {
+                DrawCommand::Pixel { point, color } => {
+                    backend.draw_pixel(*point, (*color).into())?
+                }
+                DrawCommand::Line { from, to, stroke } => backend.draw_line(*from, *to, stroke)?,
+                DrawCommand::Rect {
+                    upper_left,
+                    bottom_right,
+                    stroke,
+                    fill,
+                } => backend.draw_rect(*upper_left, *bottom_right, stroke, *fill)?,
+                DrawCommand::Path { points, stroke } => {
+                    backend.draw_path(points.iter().copied(), stroke)?
+                }
+                DrawCommand::Circle {
+                    center,
+                    radius,
+                    stroke,
+                    fill,
+                } => backend.draw_circle(*center, *radius, stroke, *fill)?,
+                DrawCommand::Polygon { points, color } => {
+                    backend.fill_polygon(points.iter().copied(), &BackendColor::from(*color))?
+                }
+                DrawCommand::MultiPolygon { contours, color } => {
+                    backend.fill_contours(contours.clone(), &BackendColor::from(*color))?
+                }
+                DrawCommand::Text {
+                    text, pos, style, ..
+                } => backend.draw_text(text, style, *pos)?,
+                DrawCommand::Bitmap {
+                    pos,
+                    width,
+                    height,
+                    rgb,
+                } => backend.blit_bitmap(*pos, (*width, *height), rgb)?,
+                DrawCommand::PushClip {
+                    upper_left,
+                    bottom_right,
+                } => backend.push_clip(*upper_left, *bottom_right)?,
+                DrawCommand::PopClip => backend.pop_clip()?,
+                DrawCommand::PushStrokeStyle(style) => backend.push_stroke_style(style.clone())?,
+                DrawCommand::PopStrokeStyle => backend.pop_stroke_style()?,
+                DrawCommand::PushFillRule(rule) => backend.push_fill_rule(*rule)?,
+                DrawCommand::PopFillRule => backend.pop_fill_rule()?,
+                DrawCommand::PushGradient(gradient) => backend.push_gradient(gradient.clone())?,
+                DrawCommand::PopGradient => backend.pop_gradient()?,
+            }
+        }
+        Ok(())
+    }
+}
+
+type MeasureText<'a> =
+    Box<dyn FnMut(&str, &TextStyle) -> Result<TextMetrics, DrawingErrorKind<Error>> + 'a>;
+
+/// A backend that doesn't paint anything but records every primitive into a [`DisplayList`].
+///
+/// Like the SVG backend of plotters, it writes into a borrowed target, so the list can be
+/// used once the drawing area is dropped:
+///
+/// ```ignore
+/// let mut list = DisplayList::default();
+/// let root = RecordingBackend::new(&mut list, (640, 480)).into_drawing_area();
+/// chart.plot(&root)?;
+/// drop(root);
+/// list.replay(&mut backend)?;
+/// ```
+pub struct RecordingBackend<'a> {
+    list: &'a mut DisplayList,
+    /// estimate_text_size only gets `&self`
+    measure_text: Option<RefCell<MeasureText<'a>>>,
+}
+
+impl<'a> RecordingBackend<'a> {
+    /// Record into `list`, replacing what it held before
+    pub fn new(list: &'a mut DisplayList, size: (u32, u32)) -> Self {
+        *list = DisplayList::new(size);
+        Self {
+            list,
+            measure_text: None,
+        }
+    }
+
+    /// Measure text with `measure` instead of the rough estimate of 0.6 em per character,
+    /// e.g. with [`GpuiBackend::text_metrics`](crate::backend::GpuiBackend::text_metrics) so the
+    /// layout matches what is painted on replay
+    pub fn measure_text(
+        mut self,
+        measure: impl FnMut(&str, &TextStyle) -> Result<TextMetrics, DrawingErrorKind<Error>> + 'a,
+    ) -> Self {
+        self.measure_text = Some(RefCell::new(Box::new(measure)));
+        self
+    }
+
+    fn record(&mut self, command: DrawCommand) -> Result<(), DrawingErrorKind<Error>> {
+        self.list.commands.push(command);
+        Ok(())
+    }
+}
+
+impl BackendState for RecordingBackend<'_> {
+    fn push_clip(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(DrawCommand::PushClip {
+            upper_left,
+            bottom_right,
+        })
+    }
+
+    fn pop_clip(&mut self) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(DrawCommand::PopClip)
+    }
+
+    fn push_stroke_style(&mut self, style: StrokeStyle) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(DrawCommand::PushStrokeStyle(style))
+    }
+
+    fn pop_stroke_style(&mut self) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(DrawCommand::PopStrokeStyle)
+    }
+
+    fn push_fill_rule(&mut self, rule: FillRule) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(DrawCommand::PushFillRule(rule))
+    }
+
+    fn pop_fill_rule(&mut self) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(DrawCommand::PopFillRule)
+    }
+
+    fn fill_contours<S: BackendStyle>(
+        &mut self,
+        contours: Vec<Vec<BackendCoord>>,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(DrawCommand::MultiPolygon {
+            contours,
+            color: style.color().into(),
+        })
+    }
+
+    fn push_gradient(&mut self, gradient: LinearGradient) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(DrawCommand::PushGradient(gradient))
+    }
+
+    fn pop_gradient(&mut self) -> Result<(), DrawingErrorKind<Error>> {
+        self.record(DrawCommand::PopGradient)
+    }
+}
+
+impl DrawingBackend for RecordingBackend<'_> {
+    type ErrorType = Error;
+
+    fn get_size(&self) -> (u32, u32) {
+        self.list.size
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        Ok(())
+    }
+
+    fn draw_pixel(
+        &mut self,
+        point: BackendCoord,
+        color: BackendColor,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(DrawCommand::Pixel {
+            point,
+            color: color.into(),
+        })
+    }
+
+    fn draw_line<S: BackendStyle>(
+        &mut self,
+        from: BackendCoord,
+        to: BackendCoord,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(DrawCommand::Line {
+            from,
+            to,
+            stroke: Stroke::of(style),
+        })
+    }
+
+    fn draw_rect<S: BackendStyle>(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(DrawCommand::Rect {
+            upper_left,
+            bottom_right,
+            stroke: Stroke::of(style),
+            fill,
+        })
+    }
+
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        path: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(DrawCommand::Path {
+            points: path.into_iter().collect(),
+            stroke: Stroke::of(style),
+        })
+    }
+
+    fn draw_circle<S: BackendStyle>(
+        &mut self,
+        center: BackendCoord,
+        radius: u32,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(DrawCommand::Circle {
+            center,
+            radius,
+            stroke: Stroke::of(style),
+            fill,
+        })
+    }
+
+    fn fill_polygon<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        vert: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(DrawCommand::Polygon {
+            points: vert.into_iter().collect(),
+            color: style.color().into(),
+        })
+    }
+
+    fn estimate_text_size<TStyle: BackendTextStyle>(
+        &self,
+        text: &str,
+        style: &TStyle,
+    ) -> Result<(u32, u32), DrawingErrorKind<Self::ErrorType>> {
+        if let Some(measure) = &self.measure_text {
+            let metrics = (measure.borrow_mut())(text, &TextStyle::of(style))?;
+            return Ok((metrics.width.ceil() as u32, metrics.height().ceil() as u32));
+        }
+        let size = style.size();
+        let chars = text_lines(text)
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0) as f64;
+        let lines = text_lines(text).count() as f64;
+        Ok((
+            (chars * size * 0.6).ceil() as u32,
+            (lines * size).ceil() as u32,
+        ))
+    }
+
+    fn draw_text<TStyle: BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &TStyle,
+        pos: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let style = TextStyle::of(style);
+        let metrics = match &self.measure_text {
+            Some(measure) => Some((measure.borrow_mut())(text, &style)?),
+            None => None,
+        };
+        self.record(DrawCommand::Text {
+            text: text.to_string(),
+            pos,
+            style,
+            metrics,
+        })
+    }
+
+    fn blit_bitmap(
+        &mut self,
+        pos: BackendCoord,
+        (width, height): (u32, u32),
+        src: &[u8],
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.record(DrawCommand::Bitmap {
+            pos,
+            width,
+            height,
+            rgb: src.to_vec(),
+        })
+    }
+}
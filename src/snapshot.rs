@@ -0,0 +1,495 @@
+use crate::element::PlottersChart;
+use crate::error::Error;
+use crate::fill::FillRule;
+use crate::gradient::{GradientAxis, LinearGradient};
+use crate::line::{LineCap, LineJoin, StrokeStyle};
+use crate::record::{Color, DisplayList, DrawCommand, HAlign, RecordingBackend, Stroke, VAlign};
+use plotters::drawing::{DrawingAreaErrorKind, IntoDrawingArea};
+use plotters_backend::BackendCoord;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Set this environment variable to write the actual output to the snapshot files instead
+/// of comparing against them
+pub const UPDATE_ENV: &str = "UPDATE_SNAPSHOTS";
+
+/// Lines of unchanged context shown around every change in a diff
+const CONTEXT: usize = 2;
+
+/// Largest LCS table built for a diff, about 64 MB. Longer dumps are compared line by line.
+const MAX_LCS_CELLS: usize = 16 << 20;
+
+/// Run `chart` on a [`RecordingBackend`] of `size` pixels without opening a window.
+///
+/// Text is measured with the rough estimate of the recording backend, so the output doesn't
+/// depend on the fonts installed on the machine running the tests.
+pub fn record(
+    size: (u32, u32),
+    chart: &mut impl for<'a> PlottersChart<RecordingBackend<'a>>,
+) -> Result<DisplayList, DrawingAreaErrorKind<Error>> {
+    let mut list = DisplayList::default();
+    {
+        let root = RecordingBackend::new(&mut list, size).into_drawing_area();
+        chart
+            .plot(&root)
+            .map_err(DrawingAreaErrorKind::BackendError)?;
+        root.present()?;
+    }
+    Ok(list)
+}
+
+/// A stable, line based dump of `list`, one primitive per line
+pub fn dump(list: &DisplayList) -> String {
+    let mut out = format!("size {}x{}\n", list.size.0, list.size.1);
+    for command in &list.commands {
+        match command {
+            DrawCommand::Pixel { point, color } => {
+                writeln!(out, "pixel {} {}", coord(*point), color_str(color))
+            }
+            DrawCommand::Line { from, to, stroke } => {
+                writeln!(
+                    out,
+                    "line {} {} {}",
+                    coord(*from),
+                    coord(*to),
+                    stroke_str(stroke)
+                )
+            }
+            DrawCommand::Rect {
+                upper_left,
+                bottom_right,
+                stroke,
+                fill,
+            } => writeln!(
+                out,
+                "rect {} {} {}{}",
+                coord(*upper_left),
+                coord(*bottom_right),
+                stroke_str(stroke),
+                fill_str(*fill)
+            ),
+            DrawCommand::Path { points, stroke } => {
+                writeln!(out, "path {} {}", coords(points), stroke_str(stroke))
+            }
+            DrawCommand::Circle {
+                center,
+                radius,
+                stroke,
+                fill,
+            } => writeln!(
+                out,
+                "circle {} r{} {}{}",
+                coord(*center),
+                radius,
+                stroke_str(stroke),
+                fill_str(*fill)
+            ),
+            DrawCommand::Polygon { points, color } => {
+                writeln!(out, "polygon {} {}", coords(points), color_str(color))
+            }
+            DrawCommand::MultiPolygon { contours, color } => {
+                let contours: Vec<String> = contours.iter().map(|c| coords(c)).collect();
+                writeln!(
+                    out,
+                    "multi-polygon [{}] {}",
+                    contours.join(", "),
+                    color_str(color)
+                )
+            }
+            DrawCommand::Text {
+                text, pos, style, ..
+            } => {
+                let h_align = match style.h_align {
+                    HAlign::Left => "left",
+                    HAlign::Center => "center",
+                    HAlign::Right => "right",
+                };
+                let v_align = match style.v_align {
+                    VAlign::Top => "top",
+                    VAlign::Center => "center",
+                    VAlign::Bottom => "bottom",
+                };
+                writeln!(
+                    out,
+                    "text {:?} {} {} {} {:.1} {}-{} rot{} {}",
+                    text,
+                    coord(*pos),
+                    style.family,
+                    style.style,
+                    style.size,
+                    h_align,
+                    v_align,
+                    style.rotation,
+                    color_str(&style.color)
+                )
+            }
+            DrawCommand::Bitmap {
+                pos,
+                width,
+                height,
+                rgb,
+            } => writeln!(
+                out,
+                "bitmap {} {}x{} {:016x}",
+                coord(*pos),
+                width,
+                height,
+                fnv1a(rgb)
+            ),
+            DrawCommand::PushClip {
+                upper_left,
+                bottom_right,
+            } => writeln!(
+                out,
+                "push-clip {} {}",
+                coord(*upper_left),
+                coord(*bottom_right)
+            ),
+            DrawCommand::PopClip => writeln!(out, "pop-clip"),
+            DrawCommand::PushStrokeStyle(style) => {
+                writeln!(out, "push-stroke-style {}", stroke_style_str(style))
+            }
+            DrawCommand::PopStrokeStyle => writeln!(out, "pop-stroke-style"),
+            DrawCommand::PushFillRule(rule) => {
+                let rule = match rule {
+                    FillRule::EvenOdd => "even-odd",
+                    FillRule::NonZero => "non-zero",
+                };
+                writeln!(out, "push-fill-rule {}", rule)
+            }
+            DrawCommand::PopFillRule => writeln!(out, "pop-fill-rule"),
+            DrawCommand::PushGradient(gradient) => {
+                writeln!(out, "push-gradient {}", gradient_str(gradient))
+            }
+            DrawCommand::PopGradient => writeln!(out, "pop-gradient"),
+        }
+        .expect("writing to a String can't fail");
+    }
+    out
+}
+
+/// A readable line diff between two dumps, `None` when they are equal.
+///
+/// Removed lines start with `-`, added lines with `+`, runs of unchanged lines far from any
+/// change are collapsed. Changes too large for the LCS are shown line by line, each changed
+/// line as a removal and an addition.
+pub fn diff(expected: &str, actual: &str) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    // only the part between the common prefix and suffix goes through the quadratic LCS
+    let prefix = expected
+        .iter()
+        .zip(&actual)
+        .take_while(|(e, a)| e == a)
+        .count();
+    let suffix = expected[prefix..]
+        .iter()
+        .rev()
+        .zip(actual[prefix..].iter().rev())
+        .take_while(|(e, a)| e == a)
+        .count();
+    let old = &expected[prefix..expected.len() - suffix];
+    let new = &actual[prefix..actual.len() - suffix];
+
+    let mut lines: Vec<(char, &str)> = expected[..prefix].iter().map(|l| (' ', *l)).collect();
+    if (old.len() + 1).saturating_mul(new.len() + 1) <= MAX_LCS_CELLS {
+        lcs_lines(old, new, &mut lines);
+    } else {
+        paired_lines(old, new, &mut lines);
+    }
+    lines.extend(
+        expected[expected.len() - suffix..]
+            .iter()
+            .map(|l| (' ', *l)),
+    );
+
+    let changed: Vec<usize> = (0..lines.len()).filter(|i| lines[*i].0 != ' ').collect();
+    let near_change = |index: usize| {
+        changed
+            .iter()
+            .any(|c| index + CONTEXT >= *c && index <= c + CONTEXT)
+    };
+    let mut out = String::new();
+    let mut skipped = false;
+    for (index, (tag, line)) in lines.iter().enumerate() {
+        if near_change(index) {
+            if skipped {
+                out.push_str("  ...\n");
+                skipped = false;
+            }
+            writeln!(out, "{} {}", tag, line).expect("writing to a String can't fail");
+        } else {
+            skipped = true;
+        }
+    }
+    if skipped {
+        out.push_str("  ...\n");
+    }
+    Some(out)
+}
+
+/// The shortest edit from `old` to `new`, through their longest common subsequence
+fn lcs_lines<'a>(old: &[&'a str], new: &[&'a str], lines: &mut Vec<(char, &'a str)>) {
+    // lcs[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(('-', old[i]));
+            i += 1;
+        } else {
+            lines.push(('+', new[j]));
+            j += 1;
+        }
+    }
+}
+
+/// Compare `old` and `new` line by line, in linear time and memory
+fn paired_lines<'a>(old: &[&'a str], new: &[&'a str], lines: &mut Vec<(char, &'a str)>) {
+    for index in 0..old.len().max(new.len()) {
+        match (old.get(index), new.get(index)) {
+            (Some(o), Some(n)) if o == n => lines.push((' ', o)),
+            (o, n) => {
+                lines.extend(o.map(|o| ('-', *o)));
+                lines.extend(n.map(|n| ('+', *n)));
+            }
+        }
+    }
+}
+
+/// Compare `actual` with the snapshot file at `path`, panicking with a diff when they differ.
+///
+/// A missing snapshot is written instead, as is every snapshot when [`UPDATE_ENV`] is set:
+///
+/// ```ignore
+/// let list = snapshot::record((640, 480), &mut LineChart::new())?;
+/// snapshot::assert_snapshot(
+///     concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/line_chart.txt"),
+///     &snapshot::dump(&list),
+/// );
+/// ```
+pub fn assert_snapshot(path: impl AsRef<Path>, actual: &str) {
+    assert_snapshot_with(path, actual, std::env::var_os(UPDATE_ENV).is_some());
+}
+
+/// [`assert_snapshot`] with the update flag passed in instead of read from [`UPDATE_ENV`]
+pub fn assert_snapshot_with(path: impl AsRef<Path>, actual: &str, update: bool) {
+    let path = path.as_ref();
+    if update || !path.exists() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .unwrap_or_else(|err| panic!("failed to create {}: {}", parent.display(), err));
+        }
+        std::fs::write(path, actual)
+            .unwrap_or_else(|err| panic!("failed to write {}: {}", path.display(), err));
+        return;
+    }
+
+    let expected = std::fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("failed to read {}: {}", path.display(), err));
+    if let Some(diff) = diff(&expected, actual) {
+        panic!(
+            "snapshot {} doesn't match, rerun with {}=1 to accept the changes\n{}",
+            path.display(),
+            UPDATE_ENV,
+            diff
+        );
+    }
+}
+
+fn coord((x, y): BackendCoord) -> String {
+    format!("({}, {})", x, y)
+}
+
+fn coords(points: &[BackendCoord]) -> String {
+    let points: Vec<String> = points.iter().map(|p| coord(*p)).collect();
+    format!("[{}]", points.join(", "))
+}
+
+fn color_str(color: &Color) -> String {
+    let (r, g, b) = color.rgb;
+    if color.alpha >= 1.0 {
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    } else {
+        format!("#{:02x}{:02x}{:02x}@{:.2}", r, g, b, color.alpha)
+    }
+}
+
+fn stroke_str(stroke: &Stroke) -> String {
+    format!("{} w{}", color_str(&stroke.color), stroke.width)
+}
+
+fn fill_str(fill: bool) -> &'static str {
+    if fill {
+        " fill"
+    } else {
+        ""
+    }
+}
+
+fn stroke_style_str(style: &StrokeStyle) -> String {
+    let cap = match style.cap {
+        LineCap::Butt => "butt",
+        LineCap::Round => "round",
+        LineCap::Square => "square",
+    };
+    let join = match style.join {
+        LineJoin::Miter => "miter",
+        LineJoin::Round => "round",
+        LineJoin::Bevel => "bevel",
+    };
+    let dash: Vec<String> = style.dash.iter().map(|d| format!("{:.1}", d)).collect();
+    format!(
+        "cap={} join={} dash=[{}] offset={:.1}",
+        cap,
+        join,
+        dash.join(", "),
+        style.dash_offset
+    )
+}
+
+fn gradient_str(gradient: &LinearGradient) -> String {
+    let axis = match gradient.axis {
+        GradientAxis::Horizontal => "horizontal",
+        GradientAxis::Vertical => "vertical",
+    };
+    let stops: Vec<String> = gradient
+        .stops
+        .iter()
+        .map(|stop| format!("{:.2} {}", stop.offset, color_str(&stop.color)))
+        .collect();
+    format!("{} [{}]", axis, stops.join(", "))
+}
+
+/// FNV-1a, unlike `DefaultHasher` its output is the same on every Rust version
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(range: std::ops::Range<usize>) -> String {
+        range.map(|i| format!("line {}\n", i)).collect()
+    }
+
+    #[test]
+    fn diff_of_equal_dumps_is_none() {
+        assert_eq!(diff("a\nb\n", "a\nb\n"), None);
+    }
+
+    #[test]
+    fn diff_shows_inserted_lines() {
+        assert_eq!(
+            diff("a\nb\nc\n", "a\nb\nx\nc\n").unwrap(),
+            "  a\n  b\n+ x\n  c\n"
+        );
+    }
+
+    #[test]
+    fn diff_shows_deleted_lines() {
+        assert_eq!(diff("a\nb\nc\n", "a\nc\n").unwrap(), "  a\n- b\n  c\n");
+    }
+
+    #[test]
+    fn diff_shows_changed_lines() {
+        assert_eq!(
+            diff("a\nb\nc\n", "a\nx\nc\n").unwrap(),
+            "  a\n- b\n+ x\n  c\n"
+        );
+    }
+
+    #[test]
+    fn diff_collapses_lines_far_from_changes() {
+        let expected = lines(0..20);
+        let actual = expected.replace("line 10\n", "changed\n");
+        assert_eq!(
+            diff(&expected, &actual).unwrap(),
+            "  ...\n  line 8\n  line 9\n- line 10\n+ changed\n  line 11\n  line 12\n  ...\n"
+        );
+    }
+
+    #[test]
+    fn diff_of_huge_changes_pairs_lines() {
+        // 5000 x 5000 lines would need a 100 MB table
+        let expected = lines(0..5000);
+        let actual = lines(1..5001);
+        let diff = diff(&expected, &actual).unwrap();
+        assert!(diff.starts_with("- line 0\n+ line 1\n- line 1\n+ line 2\n"));
+        assert_eq!(diff.lines().count(), 10000);
+    }
+
+    #[test]
+    fn dump_rounds_text_sizes_and_alphas() {
+        let mut list = DisplayList::new((10, 10));
+        list.commands.push(DrawCommand::Polygon {
+            points: vec![(0, 0), (4, 0), (0, 4)],
+            color: Color {
+                rgb: (255, 0, 16),
+                alpha: 0.3333,
+            },
+        });
+        list.commands.push(DrawCommand::PushStrokeStyle(
+            StrokeStyle::new().dash([2.25, 1.0], 0.5),
+        ));
+        assert_eq!(
+            dump(&list),
+            "size 10x10\n\
+             polygon [(0, 0), (4, 0), (0, 4)] #ff0010@0.33\n\
+             push-stroke-style cap=butt join=miter dash=[2.2, 1.0] offset=0.5\n"
+        );
+    }
+
+    #[test]
+    fn fnv1a_matches_reference_values() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn assert_snapshot_writes_missing_and_updated_snapshots() {
+        let dir =
+            std::env::temp_dir().join(format!("plotters-gpui-snapshot-{}", std::process::id()));
+        let path = dir.join("nested").join("chart.txt");
+
+        // a missing snapshot is written
+        assert_snapshot_with(&path, "old\n", false);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "old\n");
+
+        // the same dump passes
+        assert_snapshot_with(&path, "old\n", false);
+
+        // a different dump fails without touching the file
+        let result = std::panic::catch_unwind(|| assert_snapshot_with(&path, "new\n", false));
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "old\n");
+
+        // unless updates are requested
+        assert_snapshot_with(&path, "new\n", true);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new\n");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,654 @@
+use crate::bitmap::rgb_pixels;
+#[cfg(feature = "plotters")]
+use crate::element::PlottersChart;
+use crate::error::Error;
+use crate::fill::FillRule;
+use crate::gradient::{GradientAxis, LinearGradient};
+use crate::line::{Line, StrokeStyle};
+#[cfg(feature = "plotters")]
+use crate::record::RecordingBackend;
+use crate::record::{
+    Color, DisplayList, DrawCommand, HAlign, Stroke, TextMetrics, TextStyle, VAlign,
+};
+use crate::utils::{stroke_coord, text_lines};
+use gpui::{point, px};
+use image::{ImageFormat, RgbaImage};
+use lyon::math::point as lyon_point;
+use lyon::path::{Path as LyonPath, Winding};
+use lyon::tessellation::{
+    BuffersBuilder, FillTessellator, FillVertex, StrokeOptions, StrokeTessellator, StrokeVertex,
+    VertexBuffers,
+};
+#[cfg(feature = "plotters")]
+use plotters::drawing::{DrawingAreaErrorKind, IntoDrawingArea};
+use plotters_backend::{BackendCoord, BackendTextStyle, FontTransform};
+use std::path::Path;
+
+/// Samples per pixel along each axis, shapes are anti-aliased with 4x4 supersampling
+const SAMPLES: usize = 4;
+
+/// Rasterize a display list on the CPU, without a window or a GPU.
+///
+/// Shapes are tessellated with the same lyon options gpui uses for
+/// [`GpuiBackend`](crate::backend::GpuiBackend), so stroke widths, caps, joins, dashes and fill
+/// rules match the screen. Text is rasterized by the font backend of plotters and anchored
+/// with the metrics it was laid out with, which needs the `ttf` feature.
+pub fn render(list: &DisplayList) -> Result<RgbaImage, Error> {
+    let mut canvas = Canvas::new(list.size);
+    for command in &list.commands {
+        canvas.command(command)?;
+    }
+    Ok(RgbaImage::from_raw(list.size.0, list.size.1, canvas.data)
+        .expect("canvas buffer matches its size"))
+}
+
+/// Write [`render`] to a PNG file
+pub fn save_png(list: &DisplayList, path: impl AsRef<Path>) -> Result<(), Error> {
+    render(list)?
+        .save_with_format(path, ImageFormat::Png)
+        .map_err(|err| Error::Io(std::io::Error::other(err)))
+}
+
+/// Run `chart` on a [`RecordingBackend`] of `size` pixels and [`render`] it.
+///
+/// Text is measured with the font backend of plotters that rasterizes it, so axis labels are
+/// placed the way they are drawn here. Lists recorded without a measurer lay text out with
+/// a rough estimate instead.
+#[cfg(feature = "plotters")]
+pub fn render_chart(
+    size: (u32, u32),
+    chart: &mut impl for<'a> PlottersChart<RecordingBackend<'a>>,
+) -> Result<RgbaImage, DrawingAreaErrorKind<Error>> {
+    let mut list = DisplayList::default();
+    {
+        let root = RecordingBackend::new(&mut list, size)
+            .measure_text(|text, style| Ok(style.layout_metrics(text)?))
+            .into_drawing_area();
+        chart
+            .plot(&root)
+            .map_err(DrawingAreaErrorKind::BackendError)?;
+        root.present()?;
+    }
+    Ok(render(&list)?)
+}
+
+/// Write [`render_chart`] to a PNG file
+#[cfg(feature = "plotters")]
+pub fn save_chart_png(
+    size: (u32, u32),
+    chart: &mut impl for<'a> PlottersChart<RecordingBackend<'a>>,
+    path: impl AsRef<Path>,
+) -> Result<(), DrawingAreaErrorKind<Error>> {
+    render_chart(size, chart)?
+        .save_with_format(path, ImageFormat::Png)
+        .map_err(|err| Error::Io(std::io::Error::other(err)))?;
+    Ok(())
+}
+
+/// A triangle in pixel coordinates
+type Triangle = [(f32, f32); 3];
+
+struct Canvas {
+    width: u32,
+    height: u32,
+    /// RGBA with straight alpha
+    data: Vec<u8>,
+    /// coverage samples of the shape being drawn, one bit per sample
+    mask: Vec<u16>,
+    clips: Vec<(i32, i32, i32, i32)>,
+    stroke_styles: Vec<StrokeStyle>,
+    fill_rules: Vec<FillRule>,
+    gradients: Vec<LinearGradient>,
+}
+
+impl Canvas {
+    fn new((width, height): (u32, u32)) -> Self {
+        let len = width as usize * height as usize;
+        Self {
+            width,
+            height,
+            data: vec![0; len * 4],
+            mask: vec![0; len],
+            clips: vec![],
+            stroke_styles: vec![],
+            fill_rules: vec![],
+            gradients: vec![],
+        }
+    }
+
+    /// The current clip as `(x0, y0, x1, y1)`, exclusive at the bottom right
+    fn clip(&self) -> (i32, i32, i32, i32) {
+        self.clips
+            .last()
+            .copied()
+            .unwrap_or((0, 0, self.width as i32, self.height as i32))
+    }
+
+    fn command(&mut self, command: &DrawCommand) -> Result<(), Error> {
+        match command {
+            DrawCommand::Pixel { point, color } => self.blend(*point, color, 1.0),
+            DrawCommand::Line { from, to, stroke } => {
+                let points = vec![
+                    stroke_coord(*from, stroke.width),
+                    stroke_coord(*to, stroke.width),
+                ];
+                self.stroke_line(self.line(points, stroke), &stroke.color)?;
+            }
+            DrawCommand::Rect {
+                upper_left,
+                bottom_right,
+                stroke,
+                fill,
+            } => {
+                if *fill {
+                    let (x0, y0) = (upper_left.0 as f32, upper_left.1 as f32);
+                    let (x1, y1) = (bottom_right.0 as f32, bottom_right.1 as f32);
+                    let mut builder = LyonPath::builder();
+                    builder.begin(lyon_point(x0, y0));
+                    builder.line_to(lyon_point(x0, y1));
+                    builder.line_to(lyon_point(x1, y1));
+                    builder.line_to(lyon_point(x1, y0));
+                    builder.end(true);
+                    self.fill_shape(&builder.build(), &stroke.color)?;
+                } else {
+                    let (x0, y0) = stroke_coord(*upper_left, stroke.width);
+                    let (x1, y1) = stroke_coord(*bottom_right, stroke.width);
+                    let points = vec![(x0, y0), (x0, y1), (x1, y1), (x1, y0)];
+                    let line = self.line(points, stroke).closed(true);
+                    self.stroke_line(line, &stroke.color)?;
+                }
+            }
+            DrawCommand::Path { points, stroke } => {
+                if points.is_empty() {
+                    return Ok(());
+                }
+                let points = points
+                    .iter()
+                    .map(|p| stroke_coord(*p, stroke.width))
+                    .collect();
+                self.stroke_line(self.line(points, stroke), &stroke.color)?;
+            }
+            DrawCommand::Circle {
+                center,
+                radius,
+                stroke,
+                fill,
+            } => {
+                if stroke.color.alpha == 0.0 {
+                    return Ok(());
+                }
+                if *radius == 0 {
+                    self.blend(*center, &stroke.color, 1.0);
+                    return Ok(());
+                }
+                let mut builder = LyonPath::builder();
+                builder.add_circle(
+                    lyon_point(center.0 as f32, center.1 as f32),
+                    *radius as f32,
+                    Winding::Positive,
+                );
+                let path = builder.build();
+                if *fill {
+                    self.fill_path(&path, &stroke.color)?;
+                } else {
+                    let options = StrokeOptions::default().with_line_width(stroke.width as f32);
+                    self.stroke_path(&path, &options, &stroke.color)?;
+                }
+            }
+            DrawCommand::Polygon { points, color } => {
+                let Some(first) = points.first() else {
+                    return Ok(());
+                };
+                let mut builder = LyonPath::builder();
+                builder.begin(lyon_point(first.0 as f32, first.1 as f32));
+                for (x, y) in points.iter().skip(1) {
+                    builder.line_to(lyon_point(*x as f32, *y as f32));
+                }
+                builder.end(false);
+                self.fill_shape(&builder.build(), color)?;
+            }
+            DrawCommand::MultiPolygon { contours, color } => {
+                let mut builder = LyonPath::builder();
+                for contour in contours {
+                    let Some(first) = contour.first() else {
+                        continue;
+                    };
+                    builder.begin(lyon_point(first.0 as f32, first.1 as f32));
+                    for (x, y) in contour.iter().skip(1) {
+                        builder.line_to(lyon_point(*x as f32, *y as f32));
+                    }
+                    builder.end(true);
+                }
+                self.fill_shape(&builder.build(), color)?;
+            }
+            DrawCommand::Text {
+                text,
+                pos,
+                style,
+                metrics,
+            } => self.text(text, *pos, style, metrics.as_ref())?,
+            DrawCommand::Bitmap {
+                pos,
+                width,
+                height,
+                rgb,
+            } => {
+                let rgb = rgb_pixels(*width, *height, rgb)?;
+                for y in 0..*height as i32 {
+                    for x in 0..*width as i32 {
+                        let index = (y as usize * *width as usize + x as usize) * 3;
+                        let color = Color {
+                            rgb: (rgb[index], rgb[index + 1], rgb[index + 2]),
+                            alpha: 1.0,
+                        };
+                        self.blend((pos.0 + x, pos.1 + y), &color, 1.0);
+                    }
+                }
+            }
+            DrawCommand::PushClip {
+                upper_left,
+                bottom_right,
+            } => {
+                let (x0, y0, x1, y1) = self.clip();
+                let rect = (
+                    upper_left.0.min(bottom_right.0).max(x0),
+                    upper_left.1.min(bottom_right.1).max(y0),
+                    upper_left.0.max(bottom_right.0).min(x1),
+                    upper_left.1.max(bottom_right.1).min(y1),
+                );
+                self.clips.push(rect);
+            }
+            DrawCommand::PopClip => {
+                self.clips.pop();
+            }
+            DrawCommand::PushStrokeStyle(style) => self.stroke_styles.push(style.clone()),
+            DrawCommand::PopStrokeStyle => {
+                self.stroke_styles.pop();
+            }
+            DrawCommand::PushFillRule(rule) => self.fill_rules.push(*rule),
+            DrawCommand::PopFillRule => {
+                self.fill_rules.pop();
+            }
+            DrawCommand::PushGradient(gradient) => self.gradients.push(gradient.clone()),
+            DrawCommand::PopGradient => {
+                self.gradients.pop();
+            }
+        }
+        Ok(())
+    }
+
+    /// A line with the width of `stroke` and the current stroke style, like the backend builds it
+    fn line(&self, points: Vec<(f32, f32)>, stroke: &Stroke) -> Line {
+        let mut line = Line::new().width(px(stroke.width as f32));
+        line.points = points
+            .into_iter()
+            .map(|(x, y)| point(px(x), px(y)))
+            .collect();
+        if let Some(style) = self.stroke_styles.last() {
+            line = line.cap(style.cap).join(style.join).dash(
+                style.dash.iter().map(|d| px(*d)).collect::<Vec<_>>(),
+                px(style.dash_offset),
+            );
+        }
+        line
+    }
+
+    fn stroke_line(&mut self, line: Line, color: &Color) -> Result<(), Error> {
+        let mut builder = LyonPath::builder();
+        for contour in line.contours() {
+            let Some(first) = contour.first() else {
+                continue;
+            };
+            builder.begin(lyon_point(first.x.0, first.y.0));
+            for p in contour.iter().skip(1) {
+                builder.line_to(lyon_point(p.x.0, p.y.0));
+            }
+            builder.end(line.closed && line.dash.is_empty());
+        }
+        self.stroke_path(&builder.build(), &line.stroke_options(), color)
+    }
+
+    fn stroke_path(
+        &mut self,
+        path: &LyonPath,
+        options: &StrokeOptions,
+        color: &Color,
+    ) -> Result<(), Error> {
+        let mut buffers: VertexBuffers<(f32, f32), u32> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                path,
+                options,
+                &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| {
+                    (v.position().x, v.position().y)
+                }),
+            )
+            .map_err(|err| Error::PathBuild(format!("{:?}", err)))?;
+        self.fill_triangles(&buffers, |_, _| *color);
+        Ok(())
+    }
+
+    fn fill_path(&mut self, path: &LyonPath, color: &Color) -> Result<(), Error> {
+        let buffers = self.tessellate_fill(path)?;
+        self.fill_triangles(&buffers, |_, _| *color);
+        Ok(())
+    }
+
+    /// Fill a rectangle or polygon, with the current gradient spanning its bounds if any
+    fn fill_shape(&mut self, path: &LyonPath, color: &Color) -> Result<(), Error> {
+        let buffers = self.tessellate_fill(path)?;
+        let Some(gradient) = self
+            .gradients
+            .last()
+            .filter(|g| !g.stops.is_empty())
+            .cloned()
+        else {
+            self.fill_triangles(&buffers, |_, _| *color);
+            return Ok(());
+        };
+
+        let along = |(x, y): (f32, f32)| match gradient.axis {
+            GradientAxis::Horizontal => x,
+            GradientAxis::Vertical => y,
+        };
+        let (start, end) = buffers
+            .vertices
+            .iter()
+            .map(|v| along(*v))
+            .fold((f32::MAX, f32::MIN), |(min, max), v| {
+                (min.min(v), max.max(v))
+            });
+        let extent = (end - start).max(f32::EPSILON);
+        self.fill_triangles(&buffers, |x, y| {
+            gradient
+                .color_at((along((x, y)) - start) / extent)
+                .unwrap_or(*color)
+        });
+        Ok(())
+    }
+
+    fn tessellate_fill(&self, path: &LyonPath) -> Result<VertexBuffers<(f32, f32), u32>, Error> {
+        let mut buffers: VertexBuffers<(f32, f32), u32> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate_path(
+                path,
+                &self
+                    .fill_rules
+                    .last()
+                    .copied()
+                    .unwrap_or_default()
+                    .fill_options(),
+                &mut BuffersBuilder::new(&mut buffers, |v: FillVertex| {
+                    (v.position().x, v.position().y)
+                }),
+            )
+            .map_err(|err| Error::PathBuild(format!("{:?}", err)))?;
+        Ok(buffers)
+    }
+
+    /// Blend the union of the triangles with the color `paint` returns for the centre of each
+    /// pixel, every pixel once, weighted by the share of its samples covered by any triangle
+    fn fill_triangles(
+        &mut self,
+        buffers: &VertexBuffers<(f32, f32), u32>,
+        paint: impl Fn(f32, f32) -> Color,
+    ) {
+        let (cx0, cy0, cx1, cy1) = self.clip();
+        let mut bounds = (i32::MAX, i32::MAX, i32::MIN, i32::MIN);
+        for indices in buffers.indices.chunks_exact(3) {
+            let triangle: Triangle = [
+                buffers.vertices[indices[0] as usize],
+                buffers.vertices[indices[1] as usize],
+                buffers.vertices[indices[2] as usize],
+            ];
+            let min_x = triangle.iter().map(|p| p.0).fold(f32::MAX, f32::min);
+            let min_y = triangle.iter().map(|p| p.1).fold(f32::MAX, f32::min);
+            let max_x = triangle.iter().map(|p| p.0).fold(f32::MIN, f32::max);
+            let max_y = triangle.iter().map(|p| p.1).fold(f32::MIN, f32::max);
+            let x0 = (min_x.floor() as i32).max(cx0);
+            let y0 = (min_y.floor() as i32).max(cy0);
+            let x1 = (max_x.ceil() as i32).min(cx1);
+            let y1 = (max_y.ceil() as i32).min(cy1);
+            if x0 >= x1 || y0 >= y1 {
+                continue;
+            }
+            bounds = (
+                bounds.0.min(x0),
+                bounds.1.min(y0),
+                bounds.2.max(x1),
+                bounds.3.max(y1),
+            );
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let index = y as usize * self.width as usize + x as usize;
+                    self.mask[index] |= coverage(&triangle, x, y);
+                }
+            }
+        }
+
+        let (x0, y0, x1, y1) = bounds;
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let index = y as usize * self.width as usize + x as usize;
+                let samples = std::mem::take(&mut self.mask[index]);
+                if samples != 0 {
+                    let coverage = samples.count_ones() as f32 / (SAMPLES * SAMPLES) as f32;
+                    let color = paint(x as f32 + 0.5, y as f32 + 0.5);
+                    self.blend((x, y), &color, coverage);
+                }
+            }
+        }
+    }
+
+    /// Rasterize text with the font backend of plotters, the anchor is resolved on the upright
+    /// text like the backend does and the glyphs are rotated around `pos`
+    fn text(
+        &mut self,
+        text: &str,
+        pos: BackendCoord,
+        style: &TextStyle,
+        metrics: Option<&TextMetrics>,
+    ) -> Result<(), Error> {
+        if style.color.alpha == 0.0 {
+            return Ok(());
+        }
+        let layouts = text_lines(text)
+            .map(|line| style.layout_box(line).map(|layout| (line, layout)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| Error::FontLookup(err.to_string()))?;
+        // upright text was laid out by gpui, rotated text by plotters like here
+        let upright = matches!(style.transform(), FontTransform::None);
+        let metrics = metrics.filter(|_| upright);
+        let line_height = match metrics {
+            Some(metrics) => metrics.height() / layouts.len() as f32,
+            None => layouts
+                .iter()
+                .map(|(_, ((_, min_y), (_, max_y)))| (max_y - min_y) as f32)
+                .fold(0.0, f32::max),
+        };
+        let height = line_height * layouts.len() as f32;
+        let top = match style.v_align {
+            VAlign::Top => 0.0,
+            VAlign::Center => -height / 2.0,
+            VAlign::Bottom => -height,
+        };
+        let transform = style.transform();
+
+        let single_line = layouts.len() == 1;
+        for (index, (line, ((min_x, min_y), (max_x, _)))) in layouts.into_iter().enumerate() {
+            let width = match metrics {
+                Some(metrics) if single_line => metrics.width,
+                _ => (max_x - min_x) as f32,
+            };
+            let dx = match style.h_align {
+                HAlign::Left => 0.0,
+                HAlign::Center => -width / 2.0,
+                HAlign::Right => -width,
+            };
+            let (dx, dy) = (dx as i32, (top + line_height * index as f32) as i32);
+            let result = style.draw(line, (0, 0), |x, y, color| {
+                let (x, y) = transform.transform(x + dx - min_x, y + dy - min_y);
+                self.blend((pos.0 + x, pos.1 + y), &color.into(), 1.0);
+                Ok::<(), Error>(())
+            });
+            match result {
+                Ok(result) => result?,
+                Err(err) => return Err(Error::FontLookup(err.to_string())),
+            }
+        }
+        Ok(())
+    }
+
+    /// Blend `color` over the pixel at `point` with source-over, weighted by `coverage`
+    fn blend(&mut self, (x, y): BackendCoord, color: &Color, coverage: f32) {
+        let (x0, y0, x1, y1) = self.clip();
+        if x < x0 || y < y0 || x >= x1 || y >= y1 {
+            return;
+        }
+        let alpha = color.alpha.clamp(0.0, 1.0) as f32 * coverage;
+        if alpha <= 0.0 {
+            return;
+        }
+
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        let pixel = &mut self.data[index..index + 4];
+        let src = [color.rgb.0, color.rgb.1, color.rgb.2];
+        let dst_alpha = pixel[3] as f32 / 255.0;
+        let out_alpha = alpha + dst_alpha * (1.0 - alpha);
+        for (dst, src) in pixel.iter_mut().zip(src) {
+            let blended =
+                (src as f32 * alpha + *dst as f32 * dst_alpha * (1.0 - alpha)) / out_alpha;
+            *dst = blended.round() as u8;
+        }
+        pixel[3] = (out_alpha * 255.0).round() as u8;
+    }
+}
+
+/// The samples of pixel `(x, y)` inside `triangle`, one bit per sample
+fn coverage(triangle: &Triangle, x: i32, y: i32) -> u16 {
+    let [a, b, c] = *triangle;
+    let edge = |p: (f32, f32), q: (f32, f32), s: (f32, f32)| {
+        (q.0 - p.0) * (s.1 - p.1) - (q.1 - p.1) * (s.0 - p.0)
+    };
+    let area = edge(a, b, c);
+    if area == 0.0 {
+        return 0;
+    }
+
+    let mut bits = 0;
+    for sy in 0..SAMPLES {
+        for sx in 0..SAMPLES {
+            let s = (
+                x as f32 + (sx as f32 + 0.5) / SAMPLES as f32,
+                y as f32 + (sy as f32 + 0.5) / SAMPLES as f32,
+            );
+            let w0 = edge(b, c, s) * area;
+            let w1 = edge(c, a, s) * area;
+            let w2 = edge(a, b, s) * area;
+            if w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0 {
+                bits |= 1 << (sy * SAMPLES + sx);
+            }
+        }
+    }
+    bits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color(rgb: (u8, u8, u8), alpha: f64) -> Color {
+        Color { rgb, alpha }
+    }
+
+    fn pixel(image: &RgbaImage, x: u32, y: u32) -> [u8; 4] {
+        image.get_pixel(x, y).0
+    }
+
+    #[test]
+    fn filled_rect_covers_its_pixels() {
+        let mut list = DisplayList::new((8, 8));
+        list.commands.push(DrawCommand::Rect {
+            upper_left: (2, 2),
+            bottom_right: (6, 6),
+            stroke: Stroke {
+                color: color((255, 0, 0), 1.0),
+                width: 1,
+            },
+            fill: true,
+        });
+        let image = render(&list).unwrap();
+        for y in 0..8 {
+            for x in 0..8 {
+                let inside = (2..6).contains(&x) && (2..6).contains(&y);
+                let expected = if inside { [255, 0, 0, 255] } else { [0; 4] };
+                assert_eq!(pixel(&image, x, y), expected, "pixel ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn triangle_covers_part_of_a_pixel() {
+        // the samples on or above the diagonal of the pixel
+        let samples = coverage(&[(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)], 0, 0);
+        assert_eq!(samples.count_ones(), 10);
+        assert_eq!(coverage(&[(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)], 1, 0), 0);
+    }
+
+    #[test]
+    fn blend_is_source_over() {
+        let mut canvas = Canvas::new((2, 1));
+        canvas.blend((0, 0), &color((255, 255, 255), 1.0), 1.0);
+        canvas.blend((0, 0), &color((255, 0, 0), 0.5), 1.0);
+        canvas.blend((1, 0), &color((255, 0, 0), 1.0), 0.5);
+        assert_eq!(&canvas.data[..4], &[255, 128, 128, 255]);
+        assert_eq!(&canvas.data[4..], &[255, 0, 0, 128]);
+    }
+
+    #[test]
+    fn blend_is_clipped() {
+        let mut canvas = Canvas::new((2, 1));
+        canvas.clips.push((1, 0, 2, 1));
+        canvas.blend((0, 0), &color((255, 0, 0), 1.0), 1.0);
+        canvas.blend((2, 0), &color((255, 0, 0), 1.0), 1.0);
+        assert_eq!(canvas.data, vec![0; 8]);
+    }
+
+    #[cfg(all(feature = "plotters", feature = "ttf"))]
+    #[test]
+    fn text_is_measured_like_it_is_drawn() {
+        use plotters::style::{IntoFont, BLACK};
+
+        let style = TextStyle::of(&("sans-serif", 20.0).into_font().color(&BLACK));
+        let metrics = style.layout_metrics("label\nlonger label").unwrap();
+        let ((min_x, min_y), (max_x, max_y)) = style.layout_box("longer label").unwrap();
+        assert_eq!(metrics.width, (max_x - min_x) as f32);
+        assert_eq!(metrics.height(), 2.0 * (max_y - min_y) as f32);
+    }
+
+    #[cfg(all(feature = "plotters", feature = "ttf"))]
+    #[test]
+    fn ascent_is_the_baseline_of_the_glyphs() {
+        use plotters::style::{IntoFont, BLACK};
+
+        // "x" sits on the baseline and has no descender
+        let style = TextStyle::of(&("sans-serif", 40.0).into_font().color(&BLACK));
+        let metrics = style.layout_metrics("x").unwrap();
+        let mut bottom = 0;
+        style
+            .draw("x", (0, 0), |_, y, color| {
+                if color.alpha > 0.5 {
+                    bottom = bottom.max(y + 1);
+                }
+                Ok::<(), ()>(())
+            })
+            .unwrap()
+            .unwrap();
+        assert!(
+            (bottom as f32 - metrics.ascent).abs() <= 1.5,
+            "glyphs end at {}, ascent is {}",
+            bottom,
+            metrics.ascent
+        );
+    }
+}
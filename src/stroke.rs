@@ -0,0 +1,29 @@
+use crate::backend::BackendState;
+use crate::command::{with_command, BackendCommand};
+use crate::line::StrokeStyle;
+use plotters::coord::CoordTranslate;
+use plotters::drawing::{DrawingArea, DrawingAreaErrorKind};
+
+/// Draw everything `draw` strokes with the caps, joins and dash pattern of `style`.
+///
+/// Plotters styles only carry a color and a width, this is how dashed reference lines or
+/// dotted grid lines are drawn:
+///
+/// ```ignore
+/// let dotted = StrokeStyle::new().cap(LineCap::Round).dash([0.0, 4.0], 0.0);
+/// with_stroke_style(root, dotted, || {
+///     chart.draw_series(LineSeries::new(threshold, &RED))
+/// })??;
+/// ```
+pub fn with_stroke_style<DB: BackendState, CT: CoordTranslate, R>(
+    area: &DrawingArea<DB, CT>,
+    style: StrokeStyle,
+    draw: impl FnOnce() -> R,
+) -> Result<R, DrawingAreaErrorKind<crate::Error>> {
+    with_command(
+        area,
+        BackendCommand::PushStrokeStyle(style),
+        BackendCommand::PopStrokeStyle,
+        draw,
+    )
+}
@@ -0,0 +1,597 @@
+use crate::bitmap::rgb_pixels;
+use crate::error::Error;
+use crate::fill::FillRule;
+use crate::gradient::{GradientAxis, LinearGradient};
+use crate::line::{LineCap, LineJoin, StrokeStyle};
+use crate::raster::{encode_png, PixelRaster};
+use crate::record::{
+    Color, DisplayList, DrawCommand, HAlign, Stroke, TextMetrics, TextStyle, VAlign,
+};
+use crate::utils::{stroke_coord, text_lines};
+use plotters_backend::BackendCoord;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Render a display list as an SVG document of the same size.
+///
+/// The output follows the semantics of [`GpuiBackend`](crate::backend::GpuiBackend): thin
+/// strokes are snapped to pixel centres, batched pixels and bitmaps are embedded as PNG images
+/// and text is placed with the metrics it was laid out with. Use it with the list of
+/// [`PlottersDrawAreaViewer::display_list`](crate::element::PlottersDrawAreaViewer::display_list)
+/// to export exactly what is on screen, or with
+/// [`PlottersDrawAreaModel::record`](crate::element::PlottersDrawAreaModel::record) for another
+/// size.
+///
+/// Fails with [`Error::Paint`] on a bitmap whose buffer is shorter than its size.
+pub fn to_svg(list: &DisplayList) -> Result<String, Error> {
+    let mut writer = SvgWriter::new(list.size);
+    for command in &list.commands {
+        writer.command(command)?;
+    }
+    Ok(writer.finish())
+}
+
+/// Write [`to_svg`] to a file
+pub fn save_svg(list: &DisplayList, path: impl AsRef<Path>) -> Result<(), Error> {
+    std::fs::write(path, to_svg(list)?)?;
+    Ok(())
+}
+
+struct SvgWriter {
+    out: String,
+    pixels: PixelRaster,
+    stroke_styles: Vec<StrokeStyle>,
+    fill_rules: Vec<FillRule>,
+    /// ids of the active gradients, `None` for one without stops, which doesn't apply
+    gradients: Vec<Option<usize>>,
+    next_gradient_id: usize,
+    /// clip groups that are still open
+    clip_depth: usize,
+    next_clip_id: usize,
+}
+
+impl SvgWriter {
+    fn new(size: (u32, u32)) -> Self {
+        let (width, height) = size;
+        let out = format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" "#,
+                r#"viewBox="0 0 {w} {h}" xml:space="preserve">"#,
+                "\n"
+            ),
+            w = width,
+            h = height
+        );
+        Self {
+            out,
+            pixels: PixelRaster::new(width, height),
+            stroke_styles: vec![],
+            fill_rules: vec![],
+            gradients: vec![],
+            next_gradient_id: 0,
+            clip_depth: 0,
+            next_clip_id: 0,
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.flush_pixels();
+        for _ in 0..self.clip_depth {
+            self.out.push_str("</g>\n");
+        }
+        self.out.push_str("</svg>\n");
+        self.out
+    }
+
+    fn command(&mut self, command: &DrawCommand) -> Result<(), Error> {
+        if let DrawCommand::Pixel { point, color } = command {
+            self.pixels.blend(*point, (*color).into());
+            return Ok(());
+        }
+        self.flush_pixels();
+
+        match command {
+            DrawCommand::Pixel { .. } => {}
+            DrawCommand::Line { from, to, stroke } => {
+                let (x1, y1) = stroke_coord(*from, stroke.width);
+                let (x2, y2) = stroke_coord(*to, stroke.width);
+                self.element(format!(
+                    r#"<line x1="{}" y1="{}" x2="{}" y2="{}" {}/>"#,
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    self.stroke_attrs(stroke)
+                ));
+            }
+            DrawCommand::Rect {
+                upper_left,
+                bottom_right,
+                stroke,
+                fill,
+            } => {
+                if *fill {
+                    let (x, y, width, height) = rect(*upper_left, *bottom_right);
+                    self.element(format!(
+                        r#"<rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+                        x,
+                        y,
+                        width,
+                        height,
+                        self.shape_fill_attrs(&stroke.color)
+                    ));
+                } else {
+                    // one closed contour, like the backend strokes it
+                    let (x1, y1) = stroke_coord(*upper_left, stroke.width);
+                    let (x2, y2) = stroke_coord(*bottom_right, stroke.width);
+                    self.element(format!(
+                        r#"<polygon points="{},{} {},{} {},{} {},{}" {}/>"#,
+                        x1,
+                        y1,
+                        x1,
+                        y2,
+                        x2,
+                        y2,
+                        x2,
+                        y1,
+                        self.stroke_attrs(stroke)
+                    ));
+                }
+            }
+            DrawCommand::Path { points, stroke } => {
+                if points.is_empty() {
+                    return Ok(());
+                }
+                let points: Vec<String> = points
+                    .iter()
+                    .map(|p| {
+                        let (x, y) = stroke_coord(*p, stroke.width);
+                        format!("{},{}", x, y)
+                    })
+                    .collect();
+                self.element(format!(
+                    r#"<polyline points="{}" {}/>"#,
+                    points.join(" "),
+                    self.stroke_attrs(stroke)
+                ));
+            }
+            DrawCommand::Circle {
+                center,
+                radius,
+                stroke,
+                fill,
+            } => {
+                if stroke.color.alpha == 0.0 {
+                    return Ok(());
+                }
+                if *radius == 0 {
+                    self.pixels.blend(*center, stroke.color.into());
+                    return Ok(());
+                }
+                let attrs = if *fill {
+                    fill_attrs(&stroke.color)
+                } else {
+                    // circles are not affected by stroke styles in the backend either
+                    format!(
+                        r#"fill="none" {} stroke-width="{}""#,
+                        paint_attrs("stroke", &stroke.color),
+                        stroke.width
+                    )
+                };
+                self.element(format!(
+                    r#"<circle cx="{}" cy="{}" r="{}" {}/>"#,
+                    center.0, center.1, radius, attrs
+                ));
+            }
+            DrawCommand::Polygon { points, color } => {
+                if points.is_empty() {
+                    return Ok(());
+                }
+                let points: Vec<String> =
+                    points.iter().map(|(x, y)| format!("{},{}", x, y)).collect();
+                self.element(format!(
+                    r#"<polygon points="{}" {} {}/>"#,
+                    points.join(" "),
+                    self.fill_rule_attr(),
+                    self.shape_fill_attrs(color)
+                ));
+            }
+            DrawCommand::MultiPolygon { contours, color } => {
+                let mut d = String::new();
+                for contour in contours.iter().filter(|c| !c.is_empty()) {
+                    let points: Vec<String> = contour
+                        .iter()
+                        .map(|(x, y)| format!("{},{}", x, y))
+                        .collect();
+                    write!(d, "M{}Z", points.join(" L")).unwrap();
+                }
+                if d.is_empty() {
+                    return Ok(());
+                }
+                self.element(format!(
+                    r#"<path d="{}" {} {}/>"#,
+                    d,
+                    self.fill_rule_attr(),
+                    self.shape_fill_attrs(color)
+                ));
+            }
+            DrawCommand::Text {
+                text,
+                pos,
+                style,
+                metrics,
+            } => {
+                if style.color.alpha == 0.0 {
+                    return Ok(());
+                }
+                let lines: Vec<&str> = text_lines(text).collect();
+                let size = style.size as f32;
+                // rotated text is rasterized by plotters on screen and anchored with its layout
+                let metrics = if style.rotation != 0 {
+                    style.layout_metrics(text).ok().or(*metrics)
+                } else {
+                    *metrics
+                };
+                let metrics = metrics.unwrap_or(TextMetrics {
+                    width: lines
+                        .iter()
+                        .map(|line| line.chars().count())
+                        .max()
+                        .unwrap_or(0) as f32
+                        * size
+                        * 0.6,
+                    ascent: size * 0.8,
+                    descent: size * 0.2 + size * (lines.len() - 1) as f32,
+                });
+                let dx = match style.h_align {
+                    HAlign::Left => 0.0,
+                    HAlign::Center => -metrics.width / 2.0,
+                    HAlign::Right => -metrics.width,
+                };
+                let dy = match style.v_align {
+                    VAlign::Top => 0.0,
+                    VAlign::Center => -metrics.height() / 2.0,
+                    VAlign::Bottom => -metrics.height(),
+                };
+                let mut attrs = format!(
+                    r#"font-family="{}" font-size="{}" {}"#,
+                    escape(&font_family(style)),
+                    style.size,
+                    fill_attrs(&style.color)
+                );
+                match style.style.as_str() {
+                    "bold" => attrs.push_str(r#" font-weight="bold""#),
+                    "italic" => attrs.push_str(r#" font-style="italic""#),
+                    "oblique" => attrs.push_str(r#" font-style="oblique""#),
+                    _ => {}
+                }
+                // the anchor is resolved on the upright text, which is rotated around `pos`
+                if style.rotation != 0 {
+                    write!(
+                        attrs,
+                        r#" transform="rotate({} {} {})""#,
+                        style.rotation, pos.0, pos.1
+                    )
+                    .unwrap();
+                }
+                let baseline = pos.1 as f32 + dy + metrics.ascent;
+                if let [line] = lines[..] {
+                    self.element(format!(
+                        r#"<text x="{}" y="{}" {}>{}</text>"#,
+                        pos.0 as f32 + dx,
+                        baseline,
+                        attrs,
+                        escape(line)
+                    ));
+                    return Ok(());
+                }
+
+                // the metrics only cover the whole block, every line is aligned by the viewer
+                let anchor = match style.h_align {
+                    HAlign::Left => "start",
+                    HAlign::Center => "middle",
+                    HAlign::Right => "end",
+                };
+                let line_height = metrics.height() / lines.len() as f32;
+                let mut element = format!(r#"<text text-anchor="{}" {}>"#, anchor, attrs);
+                for (index, line) in lines.iter().enumerate() {
+                    write!(
+                        element,
+                        r#"<tspan x="{}" y="{}">{}</tspan>"#,
+                        pos.0,
+                        baseline + line_height * index as f32,
+                        escape(line)
+                    )
+                    .unwrap();
+                }
+                element.push_str("</text>");
+                self.element(element);
+            }
+            DrawCommand::Bitmap {
+                pos,
+                width,
+                height,
+                rgb,
+            } => {
+                if *width == 0 || *height == 0 {
+                    return Ok(());
+                }
+                let rgba: Vec<u8> = rgb_pixels(*width, *height, rgb)?
+                    .chunks_exact(3)
+                    .flat_map(|p| [p[0], p[1], p[2], 255])
+                    .collect();
+                self.image(*pos, (*width, *height), &rgba);
+            }
+            DrawCommand::PushClip {
+                upper_left,
+                bottom_right,
+            } => {
+                let id = self.next_clip_id;
+                self.next_clip_id += 1;
+                let (x, y, width, height) = rect(*upper_left, *bottom_right);
+                self.element(format!(
+                    r#"<clipPath id="clip{}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+                    id, x, y, width, height
+                ));
+                self.element(format!(r#"<g clip-path="url(#clip{})">"#, id));
+                self.clip_depth += 1;
+            }
+            DrawCommand::PopClip => {
+                if self.clip_depth > 0 {
+                    self.clip_depth -= 1;
+                    self.element("</g>".to_string());
+                }
+            }
+            DrawCommand::PushStrokeStyle(style) => self.stroke_styles.push(style.clone()),
+            DrawCommand::PopStrokeStyle => {
+                self.stroke_styles.pop();
+            }
+            DrawCommand::PushFillRule(rule) => self.fill_rules.push(*rule),
+            DrawCommand::PopFillRule => {
+                self.fill_rules.pop();
+            }
+            DrawCommand::PushGradient(gradient) => {
+                let id = (!gradient.stops.is_empty()).then(|| self.gradient(gradient));
+                self.gradients.push(id);
+            }
+            DrawCommand::PopGradient => {
+                self.gradients.pop();
+            }
+        }
+        Ok(())
+    }
+
+    fn element(&mut self, element: String) {
+        self.out.push_str(&element);
+        self.out.push('\n');
+    }
+
+    fn flush_pixels(&mut self) {
+        let Some(patch) = self.pixels.take() else {
+            return;
+        };
+        let rgba: Vec<u8> = patch
+            .bgra
+            .chunks_exact(4)
+            .flat_map(|p| [p[2], p[1], p[0], p[3]])
+            .collect();
+        self.image(patch.origin, patch.size, &rgba);
+    }
+
+    fn image(&mut self, (x, y): BackendCoord, (width, height): (u32, u32), rgba: &[u8]) {
+        let Some(png) = encode_png(width, height, rgba) else {
+            return;
+        };
+        self.element(format!(
+            r#"<image x="{}" y="{}" width="{}" height="{}" href="data:image/png;base64,{}"/>"#,
+            x,
+            y,
+            width,
+            height,
+            base64(&png)
+        ));
+    }
+
+    /// Define `gradient` in bounding box units, so it spans every shape it fills like in gpui
+    fn gradient(&mut self, gradient: &LinearGradient) -> usize {
+        let id = self.next_gradient_id;
+        self.next_gradient_id += 1;
+        let (x2, y2) = match gradient.axis {
+            GradientAxis::Horizontal => (1, 0),
+            GradientAxis::Vertical => (0, 1),
+        };
+        let mut element = format!(
+            r#"<linearGradient id="gradient{}" x1="0" y1="0" x2="{}" y2="{}">"#,
+            id, x2, y2
+        );
+        for stop in &gradient.stops {
+            let (r, g, b) = stop.color.rgb;
+            write!(
+                element,
+                r##"<stop offset="{}" stop-color="#{:02x}{:02x}{:02x}" stop-opacity="{}"/>"##,
+                stop.offset,
+                r,
+                g,
+                b,
+                stop.color.alpha.clamp(0.0, 1.0)
+            )
+            .unwrap();
+        }
+        element.push_str("</linearGradient>");
+        self.element(element);
+        id
+    }
+
+    /// Fill attributes of rectangles and polygons, which use the current gradient if any
+    fn shape_fill_attrs(&self, color: &Color) -> String {
+        match self.gradients.last() {
+            Some(Some(id)) => format!(r#"fill="url(#gradient{})""#, id),
+            _ => fill_attrs(color),
+        }
+    }
+
+    fn fill_rule_attr(&self) -> &'static str {
+        match self.fill_rules.last().copied().unwrap_or_default() {
+            FillRule::EvenOdd => r#"fill-rule="evenodd""#,
+            FillRule::NonZero => r#"fill-rule="nonzero""#,
+        }
+    }
+
+    fn stroke_attrs(&self, stroke: &Stroke) -> String {
+        let mut attrs = format!(
+            r#"fill="none" {} stroke-width="{}""#,
+            paint_attrs("stroke", &stroke.color),
+            stroke.width
+        );
+        let Some(style) = self.stroke_styles.last() else {
+            return attrs;
+        };
+        match style.cap {
+            LineCap::Butt => {}
+            LineCap::Round => attrs.push_str(r#" stroke-linecap="round""#),
+            LineCap::Square => attrs.push_str(r#" stroke-linecap="square""#),
+        }
+        match style.join {
+            LineJoin::Miter => {}
+            LineJoin::Round => attrs.push_str(r#" stroke-linejoin="round""#),
+            LineJoin::Bevel => attrs.push_str(r#" stroke-linejoin="bevel""#),
+        }
+        // the backend strokes invalid patterns solid
+        let total: f32 = style.dash.iter().sum();
+        if !style.dash.is_empty() && style.dash.iter().all(|d| *d >= 0.0) && total > 0.0 {
+            let dash: Vec<String> = style.dash.iter().map(|d| d.to_string()).collect();
+            write!(
+                attrs,
+                r#" stroke-dasharray="{}" stroke-dashoffset="{}""#,
+                dash.join(" "),
+                style.dash_offset
+            )
+            .unwrap();
+        }
+        attrs
+    }
+}
+
+fn rect(a: BackendCoord, b: BackendCoord) -> (i32, i32, i32, i32) {
+    let (x, y) = (a.0.min(b.0), a.1.min(b.1));
+    (x, y, a.0.max(b.0) - x, a.1.max(b.1) - y)
+}
+
+fn fill_attrs(color: &Color) -> String {
+    paint_attrs("fill", color)
+}
+
+/// `fill` or `stroke` attributes for a color, with an opacity when it is translucent
+fn paint_attrs(name: &str, color: &Color) -> String {
+    let (r, g, b) = color.rgb;
+    let mut attrs = format!(r##"{}="#{:02x}{:02x}{:02x}""##, name, r, g, b);
+    if color.alpha < 1.0 {
+        write!(attrs, r#" {}-opacity="{}""#, name, color.alpha.max(0.0)).unwrap();
+    }
+    attrs
+}
+
+/// The font stack of a text: the font it was measured with, so viewers lay it out like gpui
+/// did, then the family plotters asked for in case that font is not installed
+fn font_family(style: &TextStyle) -> String {
+    match &style.font {
+        Some(font) if *font != style.family => format!("'{}', {}", font, style.family),
+        _ => style.family.clone(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(font: Option<&str>) -> String {
+        let mut list = DisplayList::new((100, 100));
+        list.commands.push(DrawCommand::Text {
+            text: "label".to_string(),
+            pos: (10, 10),
+            style: TextStyle {
+                family: "sans-serif".to_string(),
+                font: font.map(str::to_string),
+                style: "normal".to_string(),
+                size: 12.0,
+                rotation: 0,
+                h_align: HAlign::Left,
+                v_align: VAlign::Top,
+                color: Color {
+                    rgb: (0, 0, 0),
+                    alpha: 1.0,
+                },
+            },
+            metrics: None,
+        });
+        to_svg(&list).unwrap()
+    }
+
+    #[test]
+    fn measured_font_comes_first() {
+        let svg = text(Some("DejaVu Sans"));
+        assert!(svg.contains(r#"font-family="&apos;DejaVu Sans&apos;, sans-serif""#));
+    }
+
+    #[test]
+    fn generic_family_without_measured_font() {
+        assert!(text(None).contains(r#"font-family="sans-serif""#));
+    }
+
+    #[test]
+    fn base64_pads_partial_chunks() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foob"), "Zm9vYg==");
+    }
+
+    #[test]
+    fn short_bitmap_is_an_error() {
+        let mut list = DisplayList::new((10, 10));
+        list.commands.push(DrawCommand::Bitmap {
+            pos: (0, 0),
+            width: 2,
+            height: 2,
+            rgb: vec![0; 11],
+        });
+        assert!(matches!(to_svg(&list), Err(Error::Paint(_))));
+    }
+}
@@ -0,0 +1,211 @@
+use gpui::SharedString;
+use plotters::chart::ChartContext;
+use plotters::coord::cartesian::Cartesian2d;
+use plotters::coord::ranged1d::{Ranged, ValueFormatter};
+use plotters::coord::CoordTranslate;
+use plotters_backend::{BackendCoord, DrawingBackend};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Side of the square cells of the hit testing grid, in backend pixels
+const CELL_SIZE: f32 = 32.0;
+
+/// A data point a tooltip can be shown for, with its values already formatted
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataPoint {
+    pub series: SharedString,
+    pub x: SharedString,
+    pub y: SharedString,
+    /// Where the point is drawn, in backend pixels
+    pub position: BackendCoord,
+}
+
+/// The data points of a chart's series, registered in [`PlottersChart::plot`] and handed out
+/// from [`PlottersChart::data_points`] so viewers can show tooltips for them:
+///
+/// ```ignore
+/// let mut points = DataPoints::default();
+/// points.add_series(&chart, "Close", data.iter().map(|d| (d.date, d.close)));
+/// self.points = Some(Rc::new(points));
+/// ```
+///
+/// [`PlottersChart::plot`]: crate::element::PlottersChart::plot
+/// [`PlottersChart::data_points`]: crate::element::PlottersChart::data_points
+#[derive(Clone, Debug, Default)]
+pub struct DataPoints {
+    points: Vec<DataPoint>,
+}
+
+impl DataPoints {
+    /// Register the points of a series drawn on `chart`, formatting the values like the labels
+    /// of its axes
+    pub fn add_series<DB, X, Y>(
+        &mut self,
+        chart: &ChartContext<DB, Cartesian2d<X, Y>>,
+        name: impl Into<SharedString>,
+        data: impl IntoIterator<Item = (X::ValueType, Y::ValueType)>,
+    ) where
+        DB: DrawingBackend,
+        X: Ranged + ValueFormatter<X::ValueType>,
+        Y: Ranged + ValueFormatter<Y::ValueType>,
+    {
+        let spec = chart.as_coord_spec();
+        self.add_series_with(
+            chart,
+            name,
+            data,
+            |x| spec.x_spec().format_ext(x),
+            |y| spec.y_spec().format_ext(y),
+        );
+    }
+
+    /// Register the points of a series drawn on `chart`, formatting the values with
+    /// `format_x` and `format_y`
+    pub fn add_series_with<DB, X, Y>(
+        &mut self,
+        chart: &ChartContext<DB, Cartesian2d<X, Y>>,
+        name: impl Into<SharedString>,
+        data: impl IntoIterator<Item = (X::ValueType, Y::ValueType)>,
+        format_x: impl Fn(&X::ValueType) -> String,
+        format_y: impl Fn(&Y::ValueType) -> String,
+    ) where
+        DB: DrawingBackend,
+        X: Ranged,
+        Y: Ranged,
+    {
+        let name = name.into();
+        let spec = chart.as_coord_spec();
+        let (xs, ys) = chart.plotting_area().get_pixel_range();
+        for value in data {
+            let position = spec.translate(&value);
+            // points zoomed or panned out of the plotting area are not shown
+            if !xs.contains(&position.0) || !ys.contains(&position.1) {
+                continue;
+            }
+            self.points.push(DataPoint {
+                series: name.clone(),
+                x: format_x(&value.0).into(),
+                y: format_y(&value.1).into(),
+                position,
+            });
+        }
+    }
+
+    pub fn points(&self) -> &[DataPoint] {
+        &self.points
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+/// Grid of the registered points, to find the one under the mouse without going through all
+/// of them on every move
+pub(crate) struct PointIndex {
+    points: Rc<DataPoints>,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl PointIndex {
+    pub fn new(points: Rc<DataPoints>) -> Self {
+        let mut cells: HashMap<_, Vec<_>> = HashMap::new();
+        for (index, point) in points.points.iter().enumerate() {
+            let (x, y) = point.position;
+            cells
+                .entry(cell(x as f32, y as f32))
+                .or_default()
+                .push(index);
+        }
+        Self { points, cells }
+    }
+
+    /// The point closest to `position` within `radius`, both in backend pixels
+    pub fn nearest(&self, (x, y): (f32, f32), radius: f32) -> Option<&DataPoint> {
+        // the cells of both corners are searched, a point at exactly `radius` on the right or
+        // bottom edge lies in the first cell past the circle
+        let (left, top) = cell(x - radius, y - radius);
+        let (right, bottom) = cell(x + radius, y + radius);
+        let distance = |point: &DataPoint| {
+            let (dx, dy) = (point.position.0 as f32 - x, point.position.1 as f32 - y);
+            dx * dx + dy * dy
+        };
+        (left..=right)
+            .flat_map(|cx| (top..=bottom).map(move |cy| (cx, cy)))
+            .filter_map(|key| self.cells.get(&key))
+            .flatten()
+            .map(|&index| &self.points.points[index])
+            .map(|point| (distance(point), point))
+            .filter(|(distance, _)| *distance <= radius * radius)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, point)| point)
+    }
+}
+
+fn cell(x: f32, y: f32) -> (i32, i32) {
+    (
+        (x / CELL_SIZE).floor() as i32,
+        (y / CELL_SIZE).floor() as i32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(positions: &[BackendCoord]) -> PointIndex {
+        let points = positions
+            .iter()
+            .enumerate()
+            .map(|(index, &position)| DataPoint {
+                series: "series".into(),
+                x: index.to_string().into(),
+                y: "0".into(),
+                position,
+            })
+            .collect();
+        PointIndex::new(Rc::new(DataPoints { points }))
+    }
+
+    fn nearest(index: &PointIndex, position: (f32, f32), radius: f32) -> Option<&str> {
+        index
+            .nearest(position, radius)
+            .map(|point| point.x.as_ref())
+    }
+
+    #[test]
+    fn point_in_a_neighbouring_cell() {
+        // the point is in the cell left of the one under the mouse
+        let index = index(&[(30, 10)]);
+        assert_eq!(nearest(&index, (34.0, 10.0), 8.0), Some("0"));
+    }
+
+    #[test]
+    fn point_just_outside_the_radius() {
+        let index = index(&[(30, 10)]);
+        assert_eq!(nearest(&index, (38.0, 10.0), 8.0), Some("0"));
+        assert_eq!(nearest(&index, (38.5, 10.0), 8.0), None);
+    }
+
+    #[test]
+    fn point_at_the_radius_on_a_cell_boundary() {
+        // the point starts the third cell, right and below the edge of the circle
+        let index = index(&[(64, 64)]);
+        assert_eq!(nearest(&index, (56.0, 64.0), 8.0), Some("0"));
+        assert_eq!(nearest(&index, (64.0, 56.0), 8.0), Some("0"));
+        assert_eq!(nearest(&index, (72.0, 64.0), 8.0), Some("0"));
+    }
+
+    #[test]
+    fn nearest_of_several() {
+        let index = index(&[(10, 10), (20, 10), (64, 64), (22, 14)]);
+        assert_eq!(nearest(&index, (21.0, 13.0), 16.0), Some("3"));
+        assert_eq!(nearest(&index, (14.0, 10.0), 16.0), Some("0"));
+    }
+
+    #[test]
+    fn empty_index() {
+        let index = index(&[]);
+        assert_eq!(nearest(&index, (0.0, 0.0), 100.0), None);
+    }
+}
@@ -1,8 +1,33 @@
 use gpui::{point, px, Hsla, Pixels, Point, Rgba};
 use plotters_backend::{BackendColor, BackendCoord};
 
-pub fn coord_to_point(origin: Point<Pixels>, coord: BackendCoord) -> Point<Pixels> {
-    origin + point(px(coord.0 as f32), px(coord.1 as f32))
+/// Convert a backend coordinate to a logical point, `scale` is the number of backend
+/// pixels per logical pixel
+pub fn coord_to_point(origin: Point<Pixels>, coord: BackendCoord, scale: f32) -> Point<Pixels> {
+    origin + point(px(coord.0 as f32 / scale), px(coord.1 as f32 / scale))
+}
+
+/// Move a point to the centre of the device pixel it falls in, so that a stroke with an odd
+/// width in device pixels covers whole pixels instead of blurring over two
+pub fn snap_to_pixel_center(p: Point<Pixels>, scale_factor: f32) -> Point<Pixels> {
+    let snap = |v: Pixels| px(((v.0 * scale_factor).floor() + 0.5) / scale_factor);
+    point(snap(p.x), snap(p.y))
+}
+
+/// Snap a backend coordinate to the pixel centre when the stroke covers an odd number of
+/// pixels, like [`snap_to_pixel_center`] does at a scale factor of 1
+pub fn stroke_coord((x, y): BackendCoord, width: u32) -> (f32, f32) {
+    if width % 2 == 1 {
+        (x as f32 + 0.5, y as f32 + 0.5)
+    } else {
+        (x as f32, y as f32)
+    }
+}
+
+/// The lines of a possibly multi-line label, `\r\n` counts as one line break
+pub fn text_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
 }
 
 pub fn color_to_hsla(color: BackendColor) -> Hsla {
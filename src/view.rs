@@ -0,0 +1,161 @@
+use std::ops::Range;
+
+/// Smallest share of the full range a view can be zoomed into, deeper zooms run into the
+/// precision of `f64` on long ranges
+const MIN_SPAN: f64 = 1e-9;
+
+/// The part of a chart's full data range that is shown, as fractions of that range along each
+/// axis. `x` runs left to right and `y` bottom to top, `(0.0, 1.0)` shows the whole axis.
+///
+/// [`PlottersDrawAreaViewer`](crate::element::PlottersDrawAreaViewer) zooms and pans it, charts
+/// map their full ranges through it when they build their coordinates:
+///
+/// ```ignore
+/// fn set_view(&mut self, view: ViewRange) {
+///     self.view = view;
+/// }
+///
+/// fn plot(&mut self, root: &DrawingArea<DB, Shift>) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
+///     let mut chart = ChartBuilder::on(root)
+///         .build_cartesian_2d(self.view.x_range(0.0..100.0), self.view.y_range(-1.0..1.0))
+///         .unwrap();
+///     ...
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewRange {
+    pub x: (f64, f64),
+    pub y: (f64, f64),
+}
+
+impl Default for ViewRange {
+    fn default() -> Self {
+        Self {
+            x: (0.0, 1.0),
+            y: (0.0, 1.0),
+        }
+    }
+}
+
+impl ViewRange {
+    /// Whether the whole chart is shown
+    pub fn is_full(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// The part of `full` shown along the x axis
+    pub fn x_range(&self, full: Range<f64>) -> Range<f64> {
+        map_range(self.x, full)
+    }
+
+    /// The part of `full` shown along the y axis
+    pub fn y_range(&self, full: Range<f64>) -> Range<f64> {
+        map_range(self.y, full)
+    }
+
+    /// Zoom in by `factor` (or out, below 1) around `anchor`, which stays in place. The anchor
+    /// is given as fractions of the current view, `(0.0, 0.0)` being its bottom left corner.
+    pub fn zoom(&mut self, factor: f64, anchor: (f64, f64)) {
+        if !(factor.is_finite() && factor > 0.0) {
+            return;
+        }
+        self.x = zoom_axis(self.x, factor, anchor.0);
+        self.y = zoom_axis(self.y, factor, anchor.1);
+    }
+
+    /// Move the view by fractions of its own size, positive values move it right and up
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        let pan_axis = |(start, end): (f64, f64), delta: f64| {
+            let offset = delta * (end - start);
+            (start + offset, end + offset)
+        };
+        self.x = pan_axis(self.x, dx);
+        self.y = pan_axis(self.y, dy);
+    }
+}
+
+fn map_range((start, end): (f64, f64), full: Range<f64>) -> Range<f64> {
+    let span = full.end - full.start;
+    full.start + start * span..full.start + end * span
+}
+
+fn zoom_axis((start, end): (f64, f64), factor: f64, anchor: f64) -> (f64, f64) {
+    let span = (end - start) / factor;
+    if span < MIN_SPAN {
+        return (start, end);
+    }
+    let center = start + anchor * (end - start);
+    (center - anchor * span, center + (1.0 - anchor) * span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: (f64, f64), b: (f64, f64)) {
+        assert!(
+            (a.0 - b.0).abs() < 1e-12 && (a.1 - b.1).abs() < 1e-12,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    /// The fraction of the full range at `anchor` of the view
+    fn at(axis: (f64, f64), anchor: f64) -> f64 {
+        axis.0 + anchor * (axis.1 - axis.0)
+    }
+
+    #[test]
+    fn zoom_keeps_the_anchor_in_place() {
+        let axis = (0.2, 0.6);
+        for anchor in [0.0, 0.25, 0.5, 1.0] {
+            for factor in [2.0, 0.5, 10.0] {
+                let zoomed = zoom_axis(axis, factor, anchor);
+                assert!((at(zoomed, anchor) - at(axis, anchor)).abs() < 1e-12);
+                assert!(((zoomed.1 - zoomed.0) - 0.4 / factor).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn zoom_stops_at_min_span() {
+        let axis = (0.0, 2.0 * MIN_SPAN);
+        assert_close(zoom_axis(axis, 2.0, 0.0), (0.0, MIN_SPAN));
+        assert_close(zoom_axis(axis, 4.0, 0.0), axis);
+    }
+
+    #[test]
+    fn zoom_ignores_invalid_factors() {
+        let mut view = ViewRange::default();
+        for factor in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            view.zoom(factor, (0.5, 0.5));
+        }
+        assert!(view.is_full());
+    }
+
+    #[test]
+    fn pan_moves_by_the_view_size() {
+        let mut view = ViewRange {
+            x: (0.2, 0.4),
+            y: (0.0, 0.5),
+        };
+        view.pan(0.5, -1.0);
+        assert_close(view.x, (0.3, 0.5));
+        assert_close(view.y, (-0.5, 0.0));
+    }
+
+    #[test]
+    fn ranges_map_into_the_full_range() {
+        let view = ViewRange {
+            x: (0.25, 0.5),
+            y: (0.5, 1.0),
+        };
+        assert_eq!(view.x_range(0.0..100.0), 25.0..50.0);
+        assert_eq!(view.y_range(-1.0..1.0), 0.0..1.0);
+    }
+}
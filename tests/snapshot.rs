@@ -0,0 +1,52 @@
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters_backend::DrawingErrorKind;
+use plotters_gpui::element::PlottersChart;
+use plotters_gpui::{snapshot, Error};
+
+struct LineChart;
+
+/// `plot` can only return backend errors, layout errors are wrapped as errors of the chart
+fn backend_error(err: DrawingAreaErrorKind<Error>) -> DrawingErrorKind<Error> {
+    match err {
+        DrawingAreaErrorKind::BackendError(err) => err,
+        err => Error::chart(err.to_string()).into(),
+    }
+}
+
+impl<DB: DrawingBackend<ErrorType = Error>> PlottersChart<DB> for LineChart {
+    fn plot(
+        &mut self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingErrorKind<DB::ErrorType>> {
+        let mut chart = ChartBuilder::on(root)
+            .margin(10)
+            .x_label_area_size(20)
+            .y_label_area_size(30)
+            .build_cartesian_2d(0..4, 0..16)
+            .map_err(backend_error)?;
+        chart
+            .configure_mesh()
+            .disable_mesh()
+            .x_labels(5)
+            .y_labels(5)
+            .draw()
+            .map_err(backend_error)?;
+        chart
+            .draw_series(LineSeries::new((0..=4).map(|x| (x, x * x)), &RED))
+            .map_err(backend_error)?;
+        Ok(())
+    }
+}
+
+#[test]
+fn line_chart() {
+    let list = snapshot::record((200, 150), &mut LineChart).unwrap();
+    snapshot::assert_snapshot(
+        concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/snapshots/line_chart.txt"
+        ),
+        &snapshot::dump(&list),
+    );
+}